@@ -0,0 +1,25 @@
+use std::time::Instant;
+
+use othello::{Board, Search};
+
+// Fixed positions, so timings stay comparable from one change to the next.
+const POSITIONS: [(&str, &str); 4] = [
+    ("opening", ".............O.....XOX.....OX....XOXX....O...X..................X"),
+    ("early midgame", "..XXX......OXO....OOOOO..O.XX....OOXX....O...X..................X"),
+    ("midgame", "..XXX.O...XXXXX..XXXOOOO.O.OX.OX.OOOOO...O.OOO.......OO.........X"),
+    ("late midgame", "..XXXOOOOOOOOOOXXOOOXOOX.OOOXXOX.OOXOOO..O.XOO....XX.OO....X....X"),
+];
+
+fn main() {
+    for (name, notation) in POSITIONS {
+        let (board, player) = Board::from_notation(notation).expect("benchmark positions are valid");
+        for depth in [4, 6, 8, 10] {
+            let mut search = Search::new();
+            let start = Instant::now();
+            let score = search.negamax(&board, depth, player);
+            let elapsed = start.elapsed();
+            let rate = search.nodes() as f64 / elapsed.as_secs_f64().max(1e-9) / 1000.0;
+            println!("{name}, depth {depth}: score {score}, {} nodes in {elapsed:?} ({rate:.0} knodes/s)", search.nodes());
+        }
+    }
+}
@@ -0,0 +1,848 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::error::OthelloError;
+use crate::notation::Command;
+use crate::zobrist;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Player { Black, White }
+
+impl Player {
+    pub fn opponent(self) -> Player {
+        match self {
+            Player::Black => Player::White,
+            Player::White => Player::Black,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Player::Black => 'X',
+            Player::White => 'O',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Direction { pub dx: i8, pub dy: i8 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position { pub x: i8, pub y: i8 }
+
+impl Position {
+    pub fn neighbor(self, d: Direction) -> Position {
+        Position { x: self.x.saturating_add(d.dx), y: self.y.saturating_add(d.dy) }
+    }
+
+    // The squares around this one on the 8x8 board, three in a corner and eight in the middle.
+    pub fn neighbors(self) -> impl Iterator<Item = Position> {
+        PLAY_DIRECTIONS.iter().map(move |&dir| self.neighbor(dir)).filter(|pos| pos.is_valid())
+    }
+
+    // On the standard 8x8 board; `Board::contains` checks a board of any size.
+    pub fn is_valid(self) -> bool {
+        self.is_within(8)
+    }
+
+    // On a `size`x`size` board.
+    pub fn is_within(self, size: usize) -> bool {
+        self.x >= 0 && (self.x as usize) < size && self.y >= 0 && (self.y as usize) < size
+    }
+
+    // The letter of column `x`, from 'a'. Boards wider than 8 go on with 'i', 'j' and so on.
+    pub fn column_letter(x: i8) -> char {
+        (b'a' as i8 + x) as u8 as char
+    }
+}
+
+// The coordinate in algebraic notation, "d3", without the newline of `Command::stringify`, on
+// a board of any size: "j10" is the far corner of a 10x10 board.
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", Position::column_letter(self.x), self.y + 1)
+    }
+}
+
+// A coordinate like "d3" or "j10": a column letter in either case then a row number, both
+// counted from 1. Which squares exist depends on the board, which checks the bounds.
+impl FromStr for Position {
+    type Err = OthelloError;
+
+    fn from_str(s: &str) -> Result<Position, OthelloError> {
+        match s.trim().as_bytes() {
+            [x, row @ ..] if x.is_ascii_alphabetic() && (1..=2).contains(&row.len()) && row.iter().all(u8::is_ascii_digit) => {
+                let row = row.iter().fold(0, |row, digit| 10 * row + (digit - b'0') as i8);
+                if row == 0 {
+                    return Err(OthelloError::MalformedCoordinate)
+                }
+                Ok(Position { x: (x.to_ascii_lowercase() - b'a') as i8, y: row - 1 })
+            }
+            _ => Err(OthelloError::MalformedCoordinate),
+        }
+    }
+}
+
+// Large enough for boards up to 11x11.
+pub(crate) type Bits = u128;
+
+// Serialized as the two bitboards, square a1 in the lowest bit; the hash is recomputed on load.
+// Boards with the same discs are equal however they were reached, the hash following the discs.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Discs", into = "Discs"))]
+pub struct Board<const N: usize = 8> {
+    black: Bits,
+    white: Bits,
+    hash: u64,
+}
+
+pub static PLAY_DIRECTIONS: [Direction; 8] = [
+    Direction {dx: -1, dy: -1}, Direction {dx: -1, dy: 0}, Direction {dx: -1, dy: 1}, 
+    Direction {dx: 0, dy: -1}, Direction {dx: 0, dy: 1},
+    Direction {dx: 1, dy: -1}, Direction {dx: 1, dy: 0}, Direction {dx: 1, dy: 1}];
+
+impl<const N: usize> Board<N> {
+    const FULL: Bits = Bits::MAX >> (Bits::BITS as usize - N * N);
+    const FIRST_FILE: Bits = {
+        let mut file = 0;
+        let mut y = 0;
+        while y < N {
+            file |= 1 << (y * N);
+            y += 1;
+        }
+        file
+    };
+    const NOT_FIRST_FILE: Bits = Self::FULL & !Self::FIRST_FILE;
+    const NOT_LAST_FILE: Bits = Self::FULL & !(Self::FIRST_FILE << (N - 1));
+
+    fn bit(p: Position) -> Bits {
+        1 << (p.x as usize + N * p.y as usize)
+    }
+
+    fn shift(bits: Bits, d: Direction) -> Bits {
+        let amount = d.dx as isize + N as isize * d.dy as isize;
+        let shifted = if amount > 0 { (bits << amount) & Self::FULL } else { bits >> -amount };
+        match d.dx {
+            1 => shifted & Self::NOT_FIRST_FILE,
+            -1 => shifted & Self::NOT_LAST_FILE,
+            _ => shifted,
+        }
+    }
+
+    fn position(index: usize) -> Position {
+        Position { x: (index % N) as i8, y: (index / N) as i8 }
+    }
+
+    pub fn initial() -> Board<N> {
+        const { assert!(N >= 4 && N.is_multiple_of(2) && N * N <= Bits::BITS as usize, "unsupported board size") };
+        let (low, high) = (N as i8 / 2 - 1, N as i8 / 2);
+        Board::from_discs(
+            Self::bit(Position { x: high, y: low }) | Self::bit(Position { x: low, y: high }),
+            Self::bit(Position { x: low, y: low }) | Self::bit(Position { x: high, y: high }),
+        )
+    }
+
+    // The discs must not overlap or lie outside the board.
+    pub(crate) fn from_discs(black: Bits, white: Bits) -> Board<N> {
+        let mut board = Board { black, white, hash: 0 };
+        board.hash = board.compute_zobrist();
+        board
+    }
+
+    // `from_discs` for discs read from outside, which may break its rules.
+    fn checked_from_discs(black: Bits, white: Bits) -> Result<Board<N>, OthelloError> {
+        if black & white != 0 {
+            return Err(OthelloError::InvalidBoard("a square is occupied by both players".to_string()))
+        }
+        if (black | white) & !Self::FULL != 0 {
+            return Err(OthelloError::InvalidBoard(format!("a disc lies outside the {N}x{N} board")))
+        }
+        Ok(Board::from_discs(black, white))
+    }
+
+    // Every square of the board, in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        (0..N * N).map(Self::position)
+    }
+
+    // Every square with the disc on it, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, Option<Player>)> + '_ {
+        self.positions().map(|pos| (pos, self.player_at(pos)))
+    }
+
+    // One character per square in row-major order, 'X', 'O' or '.', then the side to move.
+    pub fn to_notation(&self, to_move: Player) -> String {
+        let mut notation: String = self.cells().map(|(_, disc)| disc.map_or('.', Player::to_char)).collect();
+        notation.push(to_move.to_char());
+        notation
+    }
+
+    // The board of any size written by `to_notation`; `Board::from_notation` reads 8x8 ones.
+    pub fn parse_notation(notation: &str) -> Result<(Board<N>, Player), OthelloError> {
+        let to_player = |c| match c {
+            'X' => Ok(Some(Player::Black)),
+            'O' => Ok(Some(Player::White)),
+            '.' => Ok(None),
+            _ => Err(OthelloError::InvalidBoard(format!("unexpected character '{c}'"))),
+        };
+        let chars: Vec<char> = notation.trim().chars().collect();
+        if chars.len() != N * N + 1 {
+            return Err(OthelloError::InvalidBoard(format!("expected {} characters, found {}", N * N + 1, chars.len())))
+        }
+        let mut board = Board { black: 0, white: 0, hash: 0 };
+        for (index, &c) in chars[..N * N].iter().enumerate() {
+            if let Some(player) = to_player(c)? {
+                board.set_cell(Self::position(index), player);
+            }
+        }
+        match to_player(chars[N * N])? {
+            Some(to_move) => Ok((board, to_move)),
+            None => Err(OthelloError::InvalidBoard("the side to move must be 'X' or 'O'".to_string())),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        N
+    }
+
+    pub fn contains(&self, p: Position) -> bool {
+        p.is_within(N)
+    }
+
+    fn compute_zobrist(&self) -> u64 {
+        let mut hash = 0;
+        for index in 0..N * N {
+            if self.black & (1 << index) != 0 {
+                hash ^= zobrist::KEYS[Player::Black as usize][index];
+            } else if self.white & (1 << index) != 0 {
+                hash ^= zobrist::KEYS[Player::White as usize][index];
+            }
+        }
+        hash
+    }
+
+    pub fn zobrist(&self, to_move: Player) -> u64 {
+        match to_move {
+            Player::Black => self.hash,
+            Player::White => self.hash ^ zobrist::WHITE_TO_MOVE,
+        }
+    }
+
+    pub(crate) fn discs(&self, player: Player) -> Bits {
+        match player {
+            Player::Black => self.black,
+            Player::White => self.white,
+        }
+    }
+
+    fn empty(&self) -> Bits {
+        Self::FULL & !(self.black | self.white)
+    }
+
+    pub fn player_at(&self, pos: Position) -> Option<Player> {
+        if !self.contains(pos) {
+            return None
+        }
+        let mask = Self::bit(pos);
+        if self.black & mask != 0 {
+            Some(Player::Black)
+        } else if self.white & mask != 0 {
+            Some(Player::White)
+        } else {
+            None
+        }
+    }
+
+    // A population count of the player's mask, constant time without keeping separate counters
+    // in sync with every move.
+    pub fn player_score(&self, player: Player) -> i64 {
+        self.discs(player).count_ones() as i64
+    }
+
+    pub fn empty_count(&self) -> u32 {
+        self.empty().count_ones()
+    }
+
+    pub fn heuristic(&self, player: Player) -> i64 {
+        self.player_score(player) - self.player_score(player.opponent())
+    }
+
+    // The outcome by disc count once neither side can move, None while the game goes on.
+    pub fn result(&self) -> Option<Command> {
+        if !self.is_terminal() {
+            return None
+        }
+        Some(Command::Victory(match self.heuristic(Player::Black).cmp(&0) {
+            std::cmp::Ordering::Greater => Some(Player::Black),
+            std::cmp::Ordering::Less => Some(Player::White),
+            std::cmp::Ordering::Equal => None,
+        }))
+    }
+
+    // Discs of `player` next to at least one empty square.
+    pub fn frontier_discs(&self, player: Player) -> u32 {
+        let empty = self.empty();
+        let near_empty = PLAY_DIRECTIONS.iter().fold(0, |near, dir| near | Self::shift(empty, *dir));
+        (self.discs(player) & near_empty).count_ones()
+    }
+
+    // The sizes of the groups of empty squares connected through any of the eight directions.
+    pub fn empty_regions(&self) -> Vec<u32> {
+        let mut regions = Vec::new();
+        let mut remaining = self.empty();
+        while remaining != 0 {
+            let mut region = remaining & remaining.wrapping_neg();
+            loop {
+                let grown = PLAY_DIRECTIONS.iter().fold(region, |grown, dir| grown | Self::shift(region, *dir)) & remaining;
+                if grown == region {
+                    break
+                }
+                region = grown;
+            }
+            regions.push(region.count_ones());
+            remaining &= !region;
+        }
+        regions
+    }
+
+    // Discs of `player` that can never be flipped. A disc is stable when, along each of the four
+    // lines through it, the line is full or a neighbour is the board edge or another stable disc
+    // of the same colour; stability spreads from the corners until nothing changes.
+    pub fn stable_discs(&self, player: Player) -> u32 {
+        let own = self.discs(player);
+        let filled = self.black | self.white;
+        let axes = [Direction { dx: 1, dy: 0 }, Direction { dx: 0, dy: 1 }, Direction { dx: 1, dy: 1 }, Direction { dx: 1, dy: -1 }];
+        let reverse = |d: Direction| Direction { dx: -d.dx, dy: -d.dy };
+        // Squares whose neighbour in direction `d` is off the board.
+        let edge = |d: Direction| Self::FULL & !Self::shift(Self::FULL, reverse(d));
+        // Filled squares from which every square up to the edge in direction `d` is filled.
+        let filled_toward = |d: Direction| {
+            let mut full = filled & edge(d);
+            for _ in 1..N {
+                full |= filled & Self::shift(full, reverse(d));
+            }
+            full
+        };
+        let full_lines: Vec<Bits> = axes.iter().map(|&d| filled_toward(d) & filled_toward(reverse(d))).collect();
+        let mut stable: Bits = 0;
+        loop {
+            let anchored = |d: Direction| edge(d) | Self::shift(stable, reverse(d));
+            let next = axes.iter().zip(&full_lines)
+                .fold(own, |next, (&d, &full)| next & (full | anchored(d) | anchored(reverse(d))));
+            if next == stable {
+                return stable.count_ones()
+            }
+            stable = next;
+        }
+    }
+
+    // The run is collected in a mask rather than a buffer, and `shift` drops discs walking off
+    // the board, so a run of any length on any size ends at the edge.
+    fn find_bridge_candidate(&self, p: Position, d: Direction, player: Player) -> Bits {
+        let own = self.discs(player);
+        let opponent = self.discs(player.opponent());
+        let mut bridge = 0;
+        let mut current = Self::shift(Self::bit(p), d);
+        while current & opponent != 0 {
+            bridge |= current;
+            current = Self::shift(current, d);
+        }
+        if current & own != 0 { bridge } else { 0 }
+    }
+
+    fn flips(&self, p: Position, player: Player) -> Bits {
+        if !self.contains(p) || self.player_at(p).is_some() {
+            return 0
+        }
+        PLAY_DIRECTIONS.iter().fold(0, |flips, dir| flips | self.find_bridge_candidate(p, *dir, player))
+    }
+
+    pub(crate) fn legal_mask(&self, player: Player) -> Bits {
+        let own = self.discs(player);
+        let opponent = self.discs(player.opponent());
+        let empty = self.empty();
+        let mut moves = 0;
+        for dir in PLAY_DIRECTIONS.iter() {
+            let mut candidates = Self::shift(own, *dir) & opponent;
+            for _ in 0..N - 3 {
+                candidates |= Self::shift(candidates, *dir) & opponent;
+            }
+            moves |= Self::shift(candidates, *dir) & empty;
+        }
+        moves
+    }
+
+    fn set_cell(&mut self, p: Position, player: Player) {
+        let index = p.x as usize + N * p.y as usize;
+        match self.player_at(p) {
+            Some(owner) if owner == player => return,
+            Some(owner) => self.hash ^= zobrist::KEYS[owner as usize][index],
+            None => {}
+        }
+        self.hash ^= zobrist::KEYS[player as usize][index];
+        let mask = Self::bit(p);
+        match player {
+            Player::Black => {
+                self.black |= mask;
+                self.white &= !mask;
+            }
+            Player::White => {
+                self.white |= mask;
+                self.black &= !mask;
+            }
+        }
+    }
+
+    // Plays the move and returns the mask of flipped discs, or 0 if the move was illegal. The
+    // search plays moves through this without allocating.
+    fn play_flips(&mut self, p: Position, player: Player) -> Bits {
+        let flips = self.flips(p, player);
+        if flips == 0 {
+            return 0
+        }
+        self.set_cell(p, player);
+        let mut remaining = flips;
+        while remaining != 0 {
+            let index = remaining.trailing_zeros() as usize;
+            self.hash ^= zobrist::KEYS[Player::Black as usize][index] ^ zobrist::KEYS[Player::White as usize][index];
+            remaining &= remaining - 1;
+        }
+        self.black ^= flips;
+        self.white ^= flips;
+        flips
+    }
+
+    pub fn play_at(&mut self, p: Position, player: Player) -> bool {
+        self.play_flips(p, player) != 0
+    }
+
+    // Plays the move and returns the discs it flipped, not counting the one placed, in row-major
+    // order. None if the move was illegal, leaving the board untouched.
+    pub fn apply(&mut self, p: Position, player: Player) -> Option<Vec<Position>> {
+        let mut remaining = self.play_flips(p, player);
+        if remaining == 0 {
+            return None
+        }
+        let mut flipped = Vec::new();
+        while remaining != 0 {
+            flipped.push(Self::position(remaining.trailing_zeros() as usize));
+            remaining &= remaining - 1;
+        }
+        Some(flipped)
+    }
+
+    // The directions in which a move at `p` captures, in the order of `PLAY_DIRECTIONS`, each
+    // with the discs it flips that way, nearest first. Empty for an illegal move.
+    pub fn captures(&self, p: Position, player: Player) -> Vec<(Direction, Vec<Position>)> {
+        if !self.contains(p) || self.player_at(p).is_some() {
+            return Vec::new()
+        }
+        PLAY_DIRECTIONS.iter().filter_map(|&dir| {
+            let bridge = self.find_bridge_candidate(p, dir, player);
+            if bridge == 0 {
+                return None
+            }
+            let mut run = Vec::new();
+            let mut square = p.neighbor(dir);
+            while bridge & Self::bit(square) != 0 {
+                run.push(square);
+                square = square.neighbor(dir);
+            }
+            Some((dir, run))
+        }).collect()
+    }
+
+    pub fn is_legal(&self, p: Position, player: Player) -> bool {
+        self.flips(p, player) != 0
+    }
+
+    // Why a move is illegal: off the board, on a taken square or capturing nothing.
+    pub fn check_move(&self, p: Position, player: Player) -> Result<(), OthelloError> {
+        if !self.contains(p) {
+            Err(OthelloError::OutOfBounds(p))
+        } else if self.player_at(p).is_some() {
+            Err(OthelloError::Occupied(p))
+        } else if !self.is_legal(p, player) {
+            Err(OthelloError::NoCaptures(p))
+        } else {
+            Ok(())
+        }
+    }
+
+    // The number of discs playing at `p` would flip, 0 for an illegal move.
+    pub fn flip_count(&self, p: Position, player: Player) -> u32 {
+        self.flips(p, player).count_ones()
+    }
+
+    pub fn legal_moves(&self, player: Player) -> Vec<Position> {
+        let mut moves = Vec::new();
+        let mut remaining = self.legal_mask(player);
+        while remaining != 0 {
+            moves.push(Self::position(remaining.trailing_zeros() as usize));
+            remaining &= remaining - 1;
+        }
+        moves
+    }
+
+    // Neither side has a legal move, whether the board is full or not: the game is over.
+    pub fn is_terminal(&self) -> bool {
+        self.legal_mask(Player::Black) == 0 && self.legal_mask(Player::White) == 0
+    }
+}
+
+// Counts the positions reached after `depth` plies, where a forced pass is a ply and a finished
+// game is a leaf. The counts depend only on move generation, so they pin it down across refactors.
+pub fn perft<const N: usize>(board: &Board<N>, depth: usize, player: Player) -> u64 {
+    if depth == 0 {
+        return 1
+    }
+    let moves = board.legal_moves(player);
+    if moves.is_empty() {
+        if board.is_terminal() {
+            return 1
+        }
+        return perft(board, depth - 1, player.opponent())
+    }
+    moves.into_iter().map(|position| {
+        let mut child = board.clone();
+        child.play_at(position, player);
+        perft(&child, depth - 1, player.opponent())
+    }).sum()
+}
+
+// The 8x8 board is the one nearly every caller wants, and the size can't be inferred from the
+// default, so it has its own constructors.
+impl Board {
+    pub fn new() -> Board {
+        Board::initial()
+    }
+
+    pub fn from_notation(notation: &str) -> Result<(Board, Player), OthelloError> {
+        Board::parse_notation(notation)
+    }
+
+    // Black's then white's bitboard as little-endian u64s, square a1 in the lowest bit: a
+    // fixed-size key for positions stored on disk. The side to move isn't included.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&(self.black as u64).to_le_bytes());
+        bytes[8..].copy_from_slice(&(self.white as u64).to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; 16]) -> Result<Board, OthelloError> {
+        let (black, white) = bytes.split_at(8);
+        let bits = |half: &[u8]| u64::from_le_bytes(half.try_into().expect("the halves are 8 bytes")) as Bits;
+        Board::checked_from_discs(bits(black), bits(white))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Discs {
+    black: Bits,
+    white: Bits,
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> From<Board<N>> for Discs {
+    fn from(board: Board<N>) -> Discs {
+        Discs { black: board.black, white: board.white }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> TryFrom<Discs> for Board<N> {
+    type Error = OthelloError;
+
+    fn try_from(Discs { black, white }: Discs) -> Result<Board<N>, OthelloError> {
+        Board::checked_from_discs(black, white)
+    }
+}
+
+// The Zobrist key already sums up the discs, so there is no need to hash the bitboards again.
+impl<const N: usize> Hash for Board<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl<const N: usize> Default for Board<N> {
+    fn default() -> Board<N> {
+        Board::initial()
+    }
+}
+
+// A board read from its 64 squares in row-major order from a1, 'X' for black, 'O' for white
+// and anything else left empty; missing squares are empty too. For setting up test positions.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(text: &str) -> Position {
+        text.parse().unwrap()
+    }
+
+    // A board with discs on the given squares only.
+    fn board_with(black: &[&str], white: &[&str]) -> Board {
+        let (mut board, _) = Board::from_notation(&format!("{}X", ".".repeat(64))).unwrap();
+        for (player, squares) in [(Player::Black, black), (Player::White, white)] {
+            for &square in squares {
+                board.set_cell(pos(square), player);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn the_opening_moves_are_legal() {
+        let board = Board::new();
+        for square in ["d3", "c4", "f5", "e6"] {
+            assert!(board.is_legal(pos(square), Player::Black), "{square}");
+        }
+        for square in ["d4", "e4", "c3", "f6", "a1", "i3"] {
+            assert!(!board.is_legal(pos(square), Player::Black), "{square}");
+        }
+    }
+
+    #[test]
+    fn a_surrounded_square_with_no_captures_is_illegal() {
+        let ring = ["c3", "d3", "e3", "c4", "e4", "c5", "d5", "e5"];
+        // White has nothing on the far side of the ring to bridge to, and black flips nothing of
+        // its own.
+        let board = board_with(&ring, &["h8"]);
+        assert!(!board.is_legal(pos("d4"), Player::White));
+        assert!(!board.is_legal(pos("d4"), Player::Black));
+        let board = board_with(&["h8"], &ring);
+        assert!(!board.is_legal(pos("d4"), Player::Black));
+    }
+
+    #[test]
+    fn the_opening_moves_come_in_row_major_order() {
+        assert_eq!(Board::new().legal_moves(Player::Black), ["d3", "c4", "f5", "e6"].map(pos));
+    }
+
+    #[test]
+    fn the_6x6_opening_has_four_moves() {
+        let moves = ["c2", "b3", "e4", "d5"].map(pos);
+        assert_eq!(Board::<6>::initial().legal_moves(Player::Black), moves);
+    }
+
+    #[test]
+    fn a_10x10_board_plays_past_h8() {
+        let mut board = Board::<10>::initial();
+        assert!(board.contains(pos("j9")) && !Board::new().contains(pos("i3")));
+        assert!(board.play_at(pos("e4"), Player::Black));
+        assert_eq!(board.player_at(pos("e5")), Some(Player::Black));
+        assert_eq!(board.player_score(Player::Black), 4);
+    }
+
+    #[test]
+    fn coordinates_past_h8_parse() {
+        assert_eq!(pos("i3"), Position { x: 8, y: 2 });
+        assert_eq!(pos("j10"), Position { x: 9, y: 9 });
+        assert!(matches!("d0".parse::<Position>(), Err(OthelloError::MalformedCoordinate)));
+        assert!(matches!("d100".parse::<Position>(), Err(OthelloError::MalformedCoordinate)));
+        assert!(Board::<10>::initial().contains(pos("j10")));
+    }
+
+    // The published counts for the standard start; any change to move generation must keep them.
+    #[test]
+    fn perft_matches_the_known_counts() {
+        let counts: Vec<u64> = (1..=5).map(|depth| perft(&Board::new(), depth, Player::Black)).collect();
+        assert_eq!(counts, [4, 12, 56, 244, 1396]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn boards_round_trip_through_json() {
+        let json = serde_json::to_string(&Board::new()).unwrap();
+        assert_eq!(json, format!(r#"{{"black":{},"white":{}}}"#, Board::new().black, Board::new().white));
+        let read: Board = serde_json::from_str(&json).unwrap();
+        assert!(read.black == Board::new().black && read.white == Board::new().white);
+        assert_eq!(read.zobrist(Player::Black), Board::new().zobrist(Player::Black));
+        assert!(serde_json::from_str::<Board>(r#"{"black":1,"white":1}"#).is_err());
+        assert!(serde_json::from_str::<Board>(&format!(r#"{{"black":{},"white":0}}"#, 1u128 << 64)).is_err());
+    }
+
+    #[test]
+    fn notation_reads_back_what_it_writes() {
+        let opening = format!("{}OX{}XO{}X", ".".repeat(27), ".".repeat(6), ".".repeat(27));
+        assert_eq!(Board::new().to_notation(Player::Black), opening);
+        let mut board = Board::<10>::initial();
+        board.play_at(pos("e4"), Player::Black);
+        let (read, to_move) = Board::<10>::parse_notation(&board.to_notation(Player::White)).unwrap();
+        assert!(read.black == board.black && read.white == board.white && to_move == Player::White);
+        assert!(matches!(Board::from_notation(&opening[1..]), Err(OthelloError::InvalidBoard(_))));
+    }
+
+    #[test]
+    fn stability_spreads_from_a_corner_along_its_edge() {
+        let board = board_with(&["a1", "b1", "c1", "d4"], &["d1", "e4"]);
+        // a1 to c1 hang on to the corner, d4 in the middle can still be flipped.
+        assert_eq!(board.stable_discs(Player::Black), 3);
+        assert_eq!(board_with(&["d4"], &["e4"]).stable_discs(Player::Black), 0);
+    }
+
+    #[test]
+    fn apply_returns_the_flipped_discs() {
+        let mut board = Board::new();
+        assert_eq!(board.apply(pos("d3"), Player::Black), Some(vec![pos("d4")]));
+        let before = board.clone();
+        assert_eq!(board.apply(pos("a1"), Player::White), None);
+        assert!(board.black == before.black && board.white == before.white);
+        assert_eq!(board.zobrist(Player::White), before.zobrist(Player::White));
+    }
+
+    // A row of opponent discs across the whole first rank, closed or left open at the far edge.
+    fn edge_runs<const N: usize>() {
+        let square = |x: usize| Position { x: x as i8, y: 0 };
+        let mut closed = Board::<N>::from_discs(0, 0);
+        closed.set_cell(square(0), Player::Black);
+        for x in 1..N - 1 {
+            closed.set_cell(square(x), Player::White);
+        }
+        assert_eq!(closed.flip_count(square(N - 1), Player::Black), N as u32 - 2);
+        assert_eq!(closed.apply(square(N - 1), Player::Black).map(|flipped| flipped.len()), Some(N - 2));
+        assert_eq!(closed.player_score(Player::Black), N as i64);
+        let mut open = Board::<N>::from_discs(0, 0);
+        for x in 1..N {
+            open.set_cell(square(x), Player::White);
+        }
+        open.set_cell(Position { x: 0, y: 1 }, Player::Black);
+        assert!(!open.is_legal(square(0), Player::Black));
+    }
+
+    #[test]
+    fn bridges_along_a_full_edge_stop_at_the_board_edge() {
+        edge_runs::<8>();
+        edge_runs::<10>();
+    }
+
+    #[test]
+    fn each_opening_move_flips_one_disc() {
+        let board = Board::new();
+        for position in board.legal_moves(Player::Black) {
+            assert_eq!(board.flip_count(position, Player::Black), 1, "{position}");
+        }
+        assert_eq!(board.flip_count(pos("d4"), Player::Black), 0);
+        assert_eq!(board.flip_count(pos("a1"), Player::Black), 0);
+    }
+
+    #[test]
+    fn the_result_follows_the_disc_count_once_nobody_can_move() {
+        // Discs too far apart for anyone to capture, so the games are over with empties left.
+        assert_eq!(board_with(&["a1", "b1"], &["h8"]).result(), Some(Command::Victory(Some(Player::Black))));
+        assert_eq!(board_with(&["a1"], &["g8", "h8"]).result(), Some(Command::Victory(Some(Player::White))));
+        assert_eq!(board_with(&["a1"], &["h8"]).result(), Some(Command::Victory(None)));
+        assert_eq!(Board::new().result(), None);
+    }
+
+    #[test]
+    fn apply_reports_exactly_the_discs_that_changed() {
+        // c3 captures to the west, the north and the east at once.
+        let before = board_with(&["a3", "f3", "c1"], &["b3", "d3", "e3", "c2"]);
+        let mut after = before.clone();
+        let flipped = after.apply(pos("c3"), Player::Black).unwrap();
+        let changed: Vec<Position> = before.cells().zip(after.cells())
+            .filter(|((p, old), (_, new))| old != new && *p != pos("c3"))
+            .map(|((p, _), _)| p)
+            .collect();
+        assert_eq!(flipped, changed);
+        assert_eq!(flipped, [pos("c2"), pos("b3"), pos("d3"), pos("e3")]);
+        assert!(flipped.iter().all(|&p| after.player_at(p) == Some(Player::Black)));
+    }
+
+    #[test]
+    fn corners_are_written_in_algebraic_notation() {
+        let corner = |x, y| Position { x, y }.to_string();
+        assert_eq!([corner(0, 0), corner(7, 0), corner(0, 7), corner(7, 7)], ["a1", "h1", "a8", "h8"]);
+    }
+
+    #[test]
+    fn the_game_is_over_when_neither_side_can_move() {
+        let (full, _) = Board::from_notation(&format!("{}X", "XO".repeat(32))).unwrap();
+        assert!(full.empty_count() == 0 && full.is_terminal());
+        // Only black can move, taking b1 from c1.
+        let one_side = board_with(&["a1"], &["b1"]);
+        assert!(one_side.legal_moves(Player::White).is_empty() && !one_side.is_terminal());
+        let stuck = board_with(&["a1"], &["h8"]);
+        assert!(stuck.empty_count() == 62 && stuck.is_terminal());
+    }
+
+    #[test]
+    fn boards_round_trip_through_bytes() {
+        let board = board_with(&["a1", "c3", "d4", "e5"], &["h8", "d5", "f5"]);
+        let read = Board::from_bytes(&board.to_bytes()).unwrap();
+        assert_eq!(read.to_notation(Player::White), board.to_notation(Player::White));
+        assert_eq!(read.zobrist(Player::White), board.zobrist(Player::White));
+        let mut doubled = Board::new().to_bytes();
+        // d4, white in the opening, made black as well.
+        doubled[3] |= 1 << 3;
+        assert!(matches!(Board::from_bytes(&doubled), Err(OthelloError::InvalidBoard(_))));
+    }
+
+    #[test]
+    fn captures_lists_each_direction_nearest_first() {
+        let board = board_with(&["a3", "f3", "c1"], &["b3", "d3", "e3", "c2"]);
+        assert_eq!(board.captures(pos("c3"), Player::Black), [
+            (Direction { dx: -1, dy: 0 }, vec![pos("b3")]),
+            (Direction { dx: 0, dy: -1 }, vec![pos("c2")]),
+            (Direction { dx: 1, dy: 0 }, vec![pos("d3"), pos("e3")]),
+        ]);
+        assert!(board.captures(pos("h8"), Player::Black).is_empty());
+    }
+
+    #[test]
+    fn move_orders_reaching_one_position_compare_and_hash_equal() {
+        let reached = |moves: &[&str]| {
+            let mut board = Board::new();
+            for (&square, player) in moves.iter().zip([Player::Black, Player::White].into_iter().cycle()) {
+                board.apply(pos(square), player).expect(square);
+            }
+            board
+        };
+        let hashed = |board: &Board| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            board.hash(&mut hasher);
+            hasher.finish()
+        };
+        let one = reached(&["d3", "c3", "b3", "b2", "f5", "d2"]);
+        let other = reached(&["d3", "c3", "b3", "d2", "f5", "b2"]);
+        assert!(one == other);
+        assert_eq!(hashed(&one), hashed(&other));
+        assert!(one != reached(&["d3", "c3", "b3", "b2", "f5"]));
+    }
+
+    #[test]
+    fn check_move_names_why_a_move_is_illegal() {
+        let board = Board::new();
+        let reason = |text| board.check_move(pos(text), Player::Black).unwrap_err().to_string();
+        assert_eq!(reason("i3"), "i3 is outside the board");
+        assert_eq!(reason("d4"), "d4 is not a legal move, the square is already taken");
+        assert_eq!(reason("a1"), "a1 is not a legal move, it flips no disc in any direction");
+        assert!(board.check_move(pos("d3"), Player::Black).is_ok());
+    }
+
+    #[test]
+    fn neighbors_stay_on_the_board() {
+        let count = |text| pos(text).neighbors().count();
+        assert_eq!([count("a1"), count("h8"), count("a4"), count("d4")], [3, 3, 5, 8]);
+        assert_eq!(pos("a1").neighbors().collect::<Vec<_>>(), ["a2", "b1", "b2"].map(pos));
+    }
+
+    // In row-major order h3 comes right before a4, so a scan leaving the a-file that wrapped
+    // around would find black's disc there and take a4.
+    #[test]
+    fn scans_off_the_a_file_do_not_wrap_to_the_row_before() {
+        let board = board_with(&["h3"], &["a4"]);
+        assert!(!board.is_legal(pos("b4"), Player::Black));
+        assert!(board.captures(pos("b4"), Player::Black).is_empty());
+        let left = pos("a4").neighbor(Direction { dx: -1, dy: 0 });
+        assert!(left == Position { x: -1, y: 3 } && !left.is_valid());
+    }
+}
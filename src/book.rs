@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use crate::board::{Board, Player, Position};
+use crate::error::OthelloError;
+use crate::notation::{replay_transcript, Command};
+
+// Moves to play without searching, keyed by the notation of the canonical form of the position
+// and the side to move, so a game also covers its symmetric variants.
+#[derive(Debug, Default, Clone)]
+pub struct Book {
+    moves: HashMap<String, Position>,
+}
+
+impl Book {
+    pub fn new() -> Book {
+        Book::default()
+    }
+
+    // Reads games in the save format, separated by blank lines. A game that doesn't replay is
+    // left out, and its error, numbered by line of `text`, goes in the returned list.
+    pub fn parse(text: &str) -> (Book, Vec<OthelloError>) {
+        let mut book = Book::new();
+        let mut skipped = Vec::new();
+        let lines: Vec<&str> = text.lines().collect();
+        let mut start = 0;
+        while start < lines.len() {
+            let end = lines[start..].iter().position(|line| line.trim().is_empty()).map_or(lines.len(), |len| start + len);
+            if end > start {
+                match replay_transcript(&lines[start..end].join("\n")) {
+                    Ok((_, _, first, moves)) => book.add_game(&moves, first),
+                    Err(OthelloError::TranscriptLine { line, reason }) => {
+                        skipped.push(OthelloError::TranscriptLine { line: start + line, reason })
+                    }
+                    Err(err) => skipped.push(err),
+                }
+            }
+            start = end + 1;
+        }
+        (book, skipped)
+    }
+
+    // Records the move played from each position of the game, which `first` opened. The first
+    // game to reach a position decides its move.
+    pub fn add_game(&mut self, moves: &[Command], first: Player) {
+        let mut board = Board::new();
+        let mut player = first;
+        for cmd in moves {
+            match cmd {
+                Command::PlayAt(position) if board.is_legal(*position, player) => {
+                    let (canonical, symmetry) = board.canonical_with_symmetry();
+                    self.moves.entry(canonical.to_notation(player)).or_insert(symmetry.apply(*position, 8));
+                    board.play_at(*position, player);
+                }
+                Command::Pass => {}
+                _ => return,
+            }
+            player = player.opponent();
+        }
+    }
+
+    pub fn lookup(&self, board: &Board, player: Player) -> Option<Position> {
+        let (canonical, symmetry) = board.canonical_with_symmetry();
+        self.moves.get(&canonical.to_notation(player)).map(|position| symmetry.inverse().apply(*position, 8))
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+}
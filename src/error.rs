@@ -0,0 +1,55 @@
+use std::fmt;
+
+use crate::board::Position;
+
+#[derive(Debug)]
+pub enum OthelloError {
+    UnknownCommand(String),
+    OutOfBounds(Position),
+    MalformedCoordinate,
+    InvalidColor(String),
+    InvalidDepth(String),
+    InvalidArgument(String),
+    MissingValue(String),
+    Occupied(Position),
+    NoCaptures(Position),
+    IllegalPass,
+    TranscriptLine { line: usize, reason: Box<OthelloError> },
+    SequenceMove { number: usize, reason: Box<OthelloError> },
+    InvalidGgf(String),
+    InvalidBoard(String),
+    InvalidStrategy(String),
+    InvalidConfig(String),
+    InvalidWeights(String),
+    UnknownOpening(String),
+}
+
+impl fmt::Display for OthelloError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OthelloError::UnknownCommand(cmd) => write!(f, "unknown command '{cmd}'"),
+            OthelloError::OutOfBounds(pos) => write!(f, "{pos} is outside the board"),
+            OthelloError::MalformedCoordinate => write!(f, "malformed coordinate, expected a column letter then a row number, like d3"),
+            OthelloError::InvalidColor(color) => write!(f, "invalid color '{color}', expected 'black' or 'white'"),
+            OthelloError::InvalidDepth(depth) => write!(f, "invalid depth '{depth}', expected a positive integer"),
+            OthelloError::InvalidArgument(arg) => write!(f, "unexpected argument '{arg}'"),
+            OthelloError::MissingValue(option) => write!(f, "missing value for '{option}'"),
+            OthelloError::Occupied(pos) => write!(f, "{pos} is not a legal move, the square is already taken"),
+            OthelloError::NoCaptures(pos) => write!(f, "{pos} is not a legal move, it flips no disc in any direction"),
+            OthelloError::IllegalPass => write!(f, "can't pass while there are legal moves"),
+            OthelloError::TranscriptLine { line, reason } => write!(f, "line {line}: {reason}"),
+            OthelloError::SequenceMove { number, reason } => write!(f, "move {number}: {reason}"),
+            OthelloError::InvalidGgf(reason) => write!(f, "invalid GGF record: {reason}"),
+            OthelloError::InvalidBoard(reason) => write!(f, "invalid board: {reason}"),
+            OthelloError::InvalidStrategy(strategy) => write!(f, "invalid strategy '{strategy}', expected 'random', 'greedy' or 'search'"),
+            OthelloError::InvalidConfig(config) => write!(f, "invalid configuration '{config}', expected STRATEGY[:DEPTH[:HEURISTIC]]"),
+            OthelloError::InvalidWeights(reason) => write!(f, "invalid weights: {reason}"),
+            OthelloError::UnknownOpening(name) => {
+                let names: Vec<&str> = crate::opening::OPENINGS.iter().map(|(name, _)| *name).collect();
+                write!(f, "unknown opening '{name}', expected one of {}", names.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for OthelloError {}
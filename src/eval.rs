@@ -0,0 +1,325 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{Bits, Board, Player};
+use crate::error::OthelloError;
+
+pub(crate) static POSITIONAL_WEIGHTS: [i64; 64] = [
+    100, -20,  10,   5,   5,  10, -20, 100,
+    -20, -50,  -2,  -2,  -2,  -2, -50, -20,
+     10,  -2,  -1,  -1,  -1,  -1,  -2,  10,
+      5,  -2,  -1,  -1,  -1,  -1,  -2,   5,
+      5,  -2,  -1,  -1,  -1,  -1,  -2,   5,
+     10,  -2,  -1,  -1,  -1,  -1,  -2,  10,
+    -20, -50,  -2,  -2,  -2,  -2, -50, -20,
+    100, -20,  10,   5,   5,  10, -20, 100,
+];
+
+fn weighted_sum(discs: Bits, squares: &[i64; 64]) -> i64 {
+    let mut sum = 0;
+    let mut remaining = discs;
+    while remaining != 0 {
+        sum += squares[remaining.trailing_zeros() as usize];
+        remaining &= remaining - 1;
+    }
+    sum
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights {
+    pub disc: i64,
+    pub positional: i64,
+    pub mobility: i64,
+    pub stability: i64,
+    pub frontier: i64,
+    pub parity: i64,
+    // The value of a disc on each square for the positional term, in row-major order.
+    pub squares: [i64; 64],
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights { disc: 1, positional: 1, mobility: 5, stability: 10, frontier: 3, parity: 0, squares: POSITIONAL_WEIGHTS }
+    }
+}
+
+// The weights with a single value, by the names of the weights file.
+pub const SCALAR_WEIGHTS: [&str; 6] = ["disc", "positional", "mobility", "stability", "frontier", "parity"];
+
+impl Weights {
+    pub fn scalars(&self) -> [(&'static str, i64); 6] {
+        [
+            ("disc", self.disc),
+            ("positional", self.positional),
+            ("mobility", self.mobility),
+            ("stability", self.stability),
+            ("frontier", self.frontier),
+            ("parity", self.parity),
+        ]
+    }
+
+    // The scalar weight called `name`, one of `SCALAR_WEIGHTS`.
+    pub fn scalar_mut(&mut self, name: &str) -> Option<&mut i64> {
+        match name {
+            "disc" => Some(&mut self.disc),
+            "positional" => Some(&mut self.positional),
+            "mobility" => Some(&mut self.mobility),
+            "stability" => Some(&mut self.stability),
+            "frontier" => Some(&mut self.frontier),
+            "parity" => Some(&mut self.parity),
+            _ => None,
+        }
+    }
+}
+
+// Writes the weights in the format `from_str` reads, every weight included.
+impl fmt::Display for Weights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in self.scalars() {
+            writeln!(f, "{name} {value}")?;
+        }
+        writeln!(f, "squares")?;
+        for row in self.squares.chunks(8) {
+            let row: Vec<String> = row.iter().map(|value| format!("{value:4}")).collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_integers(line: usize, text: &str) -> Result<Vec<i64>, OthelloError> {
+    text.split_whitespace()
+        .map(|word| word.parse().map_err(|_| OthelloError::InvalidWeights(format!("line {line}: '{word}' is not an integer"))))
+        .collect()
+}
+
+// One weight per line as "NAME VALUE", and "squares" alone on a line followed by eight rows of
+// eight values, from a1 to h8. Weights left out keep their default; '#' starts a comment.
+impl FromStr for Weights {
+    type Err = OthelloError;
+
+    fn from_str(text: &str) -> Result<Weights, OthelloError> {
+        let mut weights = Weights::default();
+        let mut lines = text.lines().enumerate()
+            .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or_default().trim()))
+            .filter(|(_, line)| !line.is_empty());
+        while let Some((line, content)) = lines.next() {
+            let (name, values) = content.split_once(char::is_whitespace).unwrap_or((content, ""));
+            let values = parse_integers(line, values)?;
+            let weight = match name {
+                "squares" if values.is_empty() => {
+                    for row in 0..8 {
+                        let Some((line, content)) = lines.next() else {
+                            return Err(OthelloError::InvalidWeights(format!("expected 8 rows of squares, found {row}")))
+                        };
+                        let values = parse_integers(line, content)?;
+                        if values.len() != 8 {
+                            return Err(OthelloError::InvalidWeights(format!("line {line}: expected 8 squares in a row, found {}", values.len())))
+                        }
+                        weights.squares[8 * row..8 * row + 8].copy_from_slice(&values);
+                    }
+                    continue
+                }
+                "squares" => return Err(OthelloError::InvalidWeights(format!("line {line}: the rows of squares go on the lines after 'squares'"))),
+                _ => match weights.scalar_mut(name) {
+                    Some(weight) => weight,
+                    None => return Err(OthelloError::InvalidWeights(format!("line {line}: unknown weight '{name}', expected one of {}, squares", SCALAR_WEIGHTS.join(", ")))),
+                },
+            };
+            match values[..] {
+                [value] => *weight = value,
+                _ => return Err(OthelloError::InvalidWeights(format!("line {line}: expected one value for '{name}', found {}", values.len()))),
+            }
+        }
+        Ok(weights)
+    }
+}
+
+// How far the game has gone, from the number of empty squares: the opening is the first 20
+// moves, the endgame the last 16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Opening,
+    Midgame,
+    Endgame,
+}
+
+impl Phase {
+    pub fn of(board: &Board) -> Phase {
+        match board.empty_count() {
+            40.. => Phase::Opening,
+            17..=39 => Phase::Midgame,
+            _ => Phase::Endgame,
+        }
+    }
+}
+
+// A set of weights for each phase. By default mobility counts most in the opening and discs
+// in the endgame, with the midgame on the usual blend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhasedWeights {
+    pub opening: Weights,
+    pub midgame: Weights,
+    pub endgame: Weights,
+}
+
+impl PhasedWeights {
+    pub fn for_phase(&self, phase: Phase) -> &Weights {
+        match phase {
+            Phase::Opening => &self.opening,
+            Phase::Midgame => &self.midgame,
+            Phase::Endgame => &self.endgame,
+        }
+    }
+}
+
+impl Default for PhasedWeights {
+    fn default() -> PhasedWeights {
+        PhasedWeights {
+            opening: Weights { disc: 0, mobility: 8, ..Weights::default() },
+            midgame: Weights::default(),
+            endgame: Weights { disc: 3, mobility: 3, ..Weights::default() },
+        }
+    }
+}
+
+impl Board {
+    pub fn positional_heuristic(&self, player: Player) -> i64 {
+        self.weighted_positional(player, &POSITIONAL_WEIGHTS)
+    }
+
+    fn weighted_positional(&self, player: Player, squares: &[i64; 64]) -> i64 {
+        weighted_sum(self.discs(player), squares) - weighted_sum(self.discs(player.opponent()), squares)
+    }
+
+    pub fn mobility(&self, player: Player) -> i64 {
+        self.legal_mask(player).count_ones() as i64 - self.legal_mask(player.opponent()).count_ones() as i64
+    }
+
+    pub fn stability(&self, player: Player) -> i64 {
+        self.stable_discs(player) as i64 - self.stable_discs(player.opponent()) as i64
+    }
+
+    pub fn frontier(&self, player: Player) -> i64 {
+        self.frontier_discs(player) as i64 - self.frontier_discs(player.opponent()) as i64
+    }
+
+    // The side to move can take the last move of a region with an odd number of empty squares
+    // by playing there first. Counts those regions, for the side to move.
+    pub fn parity(&self) -> i64 {
+        self.empty_regions().iter().filter(|&&size| size % 2 == 1).count() as i64
+    }
+
+    // The frontier term counts against the player: discs next to empty squares give the
+    // opponent moves. The parity term assumes `player` is the side to move.
+    pub fn blended_heuristic(&self, player: Player, weights: &Weights) -> i64 {
+        weights.disc * self.heuristic(player)
+            + weights.positional * self.weighted_positional(player, &weights.squares)
+            + weights.mobility * self.mobility(player)
+            + weights.stability * self.stability(player)
+            - weights.frontier * self.frontier(player)
+            + weights.parity * self.parity()
+    }
+
+    // The blend with the weights of `phase`, whatever phase the board is actually in.
+    pub fn phased_heuristic(&self, player: Player, weights: &PhasedWeights, phase: Phase) -> i64 {
+        self.blended_heuristic(player, weights.for_phase(phase))
+    }
+}
+
+// The weights are copied once per search and evaluated by reference, so their size doesn't
+// matter enough to box them and give up `Copy`.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Heuristic {
+    #[default]
+    DiscCount,
+    Positional,
+    Blended(Weights),
+    Phased(PhasedWeights),
+}
+
+impl Heuristic {
+    pub fn evaluate(&self, board: &Board, player: Player) -> i64 {
+        match self {
+            Heuristic::DiscCount => board.heuristic(player),
+            Heuristic::Positional => board.positional_heuristic(player),
+            Heuristic::Blended(weights) => board.blended_heuristic(player, weights),
+            Heuristic::Phased(weights) => board.phased_heuristic(player, weights, Phase::of(board)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Position;
+    use crate::search::Search;
+
+    // The move black picks in `notation` by searching `depth` plies past each move with
+    // `heuristic`.
+    fn choice(notation: &str, depth: usize, heuristic: Heuristic) -> Position {
+        let (board, _) = Board::from_notation(notation).unwrap();
+        let mut search = Search::new().with_heuristic(heuristic);
+        let mut best: Option<(Position, i64)> = None;
+        for position in board.legal_moves(Player::Black) {
+            let mut child = board.clone();
+            child.play_at(position, Player::Black);
+            let score = -search.negamax(&child, depth, Player::White);
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((position, score));
+            }
+        }
+        best.unwrap().0
+    }
+
+    fn pos(text: &str) -> Position {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn the_positional_heuristic_takes_the_corner_disc_count_passes_up() {
+        let notation = "............OOO...XOOO.X.X.XOOXOOOOOXXOO.O.XOX....OXXOX....X.XO.X";
+        assert_eq!(choice(notation, 0, Heuristic::Positional), pos("h8"));
+        assert_eq!(choice(notation, 0, Heuristic::DiscCount), pos("f1"));
+    }
+
+    // A midgame position, 30 empties, where the blend's mobility term decides black's move.
+    const MIDGAME: &str = "......X...X...XO.X.XOOO.X.XOXOX..XOXXXX.OOOXXOX...OOOOX...O.....X";
+
+    #[test]
+    fn mobility_changes_the_move_in_the_midgame() {
+        assert_eq!(choice(MIDGAME, 1, Heuristic::Blended(Weights::default())), pos("b4"));
+        assert_eq!(choice(MIDGAME, 1, Heuristic::Blended(Weights { mobility: 0, ..Weights::default() })), pos("f2"));
+    }
+
+    #[test]
+    fn the_frontier_term_changes_the_preferred_move() {
+        assert_eq!(choice(MIDGAME, 1, Heuristic::Blended(Weights { frontier: 0, ..Weights::default() })), pos("h3"));
+        let notation = "......XO....O.X...X.OOX...XXOXOO.XOXO....OXOOX..OOOOOXX.X.O.X.X.X";
+        assert_eq!(choice(notation, 1, Heuristic::Blended(Weights::default())), pos("d3"));
+        assert_eq!(choice(notation, 1, Heuristic::Blended(Weights { frontier: 0, ..Weights::default() })), pos("f2"));
+    }
+
+    #[test]
+    fn region_parity_changes_the_move_near_the_end() {
+        let (board, player) = Board::from_notation("X.OOOOOOXX.OO.XO.XXXOOX..OXXOOXOXOXOOOX.OOOOOXXXOOOOXX..O.OX.XX.X").unwrap();
+        assert_eq!(board.empty_regions().len(), 8);
+        // Two plies deep with the solver off, so the heuristic still decides.
+        let pick = |weights| Search::new().with_heuristic(Heuristic::Blended(weights)).with_endgame_empties(0).choose_move(&board, player, 2).unwrap().0;
+        assert_eq!(pick(Weights::default()), pos("f2"));
+        assert_eq!(pick(Weights { parity: 10, ..Weights::default() }), pos("a3"));
+    }
+
+    #[test]
+    fn one_board_scores_differently_by_phase() {
+        let mut board = Board::new();
+        board.play_at(pos("d3"), Player::Black);
+        let weights = PhasedWeights::default();
+        let opening = board.phased_heuristic(Player::Black, &weights, Phase::Opening);
+        let endgame = board.phased_heuristic(Player::Black, &weights, Phase::Endgame);
+        assert_ne!(opening, endgame);
+        assert_eq!(endgame - opening, 3 * board.heuristic(Player::Black) - 5 * board.mobility(Player::Black));
+        assert_eq!(Heuristic::Phased(weights).evaluate(&board, Player::Black), opening);
+    }
+}
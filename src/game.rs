@@ -0,0 +1,173 @@
+use crate::board::{Board, Player};
+use crate::error::OthelloError;
+use crate::notation::{transcript, Command};
+
+// A game in progress: the board, whose turn it is and every move played so far, passes
+// included, so that it can be saved, replayed or taken back. Moves taken back can be played
+// again until another move is made.
+#[derive(Clone)]
+pub struct Game {
+    board: Board,
+    to_move: Player,
+    first: Player,
+    // The position the game started from, when it isn't the initial one.
+    start: Option<Board>,
+    history: Vec<Command>,
+    // Moves undone, the last one undone on top.
+    undone: Vec<Command>,
+}
+
+impl Game {
+    pub fn new() -> Game {
+        Game::starting_with(Player::Black)
+    }
+
+    // A game from the initial position in which `first` moves first.
+    pub fn starting_with(first: Player) -> Game {
+        Game { board: Board::new(), to_move: first, first, start: None, history: Vec::new(), undone: Vec::new() }
+    }
+
+    // A game from any position, such as a puzzle read from board notation.
+    pub fn from_position(board: Board, to_move: Player) -> Game {
+        Game { board: board.clone(), to_move, first: to_move, start: Some(board), history: Vec::new(), undone: Vec::new() }
+    }
+
+    // The game reached by playing `moves` from the initial position, `first` to move.
+    pub fn from_history(moves: Vec<Command>, first: Player) -> Result<Game, OthelloError> {
+        let (board, to_move) = Board::from_transcript(&moves, first)?;
+        Ok(Game { board, to_move, first, start: None, history: moves, undone: Vec::new() })
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    pub fn history(&self) -> &[Command] {
+        &self.history
+    }
+
+    // The side that made the first move of the history.
+    pub fn first(&self) -> Player {
+        self.first
+    }
+
+    // The game as a transcript, None for a game from another position, which a list of moves
+    // can't describe.
+    pub fn transcript(&self) -> Option<String> {
+        self.start.is_none().then(|| transcript(&self.history, self.first))
+    }
+
+    // The moves so far, numbered by pairs from the side that moved first:
+    // "1. X d3  O c5  2. X f5".
+    pub fn move_list(&self) -> String {
+        let mut player = self.first;
+        let moves: Vec<String> = self.history.iter().enumerate().map(|(index, cmd)| {
+            let number = if index % 2 == 0 { format!("{}. ", index / 2 + 1) } else { String::new() };
+            let described = format!("{number}{} {}", player.to_char(), Command::stringify(cmd).trim_end());
+            player = player.opponent();
+            described
+        }).collect();
+        moves.join("  ")
+    }
+
+    // Plays a move or a pass for the side to move, which forgets the moves that were undone.
+    // Illegal moves and commands that aren't moves leave the game as it was.
+    pub fn play(&mut self, cmd: Command) -> Result<(), OthelloError> {
+        self.advance(cmd)?;
+        self.undone.clear();
+        Ok(())
+    }
+
+    fn advance(&mut self, cmd: Command) -> Result<(), OthelloError> {
+        match cmd {
+            Command::PlayAt(position) => {
+                self.board.check_move(position, self.to_move)?;
+                self.board.play_at(position, self.to_move);
+            }
+            Command::Pass => {}
+            _ => return Err(OthelloError::UnknownCommand(Command::stringify(&cmd).trim_end().to_string())),
+        }
+        self.history.push(cmd);
+        self.to_move = self.to_move.opponent();
+        Ok(())
+    }
+
+    // Takes back the last move and returns it, None at the start of the game.
+    pub fn undo(&mut self) -> Option<Command> {
+        let mut history = std::mem::take(&mut self.history);
+        let last = history.pop()?;
+        let mut undone = std::mem::take(&mut self.undone);
+        *self = match self.start.take() {
+            Some(start) => Game::from_position(start, self.first),
+            None => Game::starting_with(self.first),
+        };
+        for cmd in history {
+            self.advance(cmd).expect("the history only holds legal moves");
+        }
+        undone.push(last.clone());
+        self.undone = undone;
+        Some(last)
+    }
+
+    // Plays the last move undone again and returns it, None when there is none.
+    pub fn redo(&mut self) -> Option<Command> {
+        let cmd = self.undone.pop()?;
+        self.advance(cmd.clone()).expect("undone moves were legal in this position");
+        Some(cmd)
+    }
+
+    // Over once neither side can move, the board being full or not, or both sides have passed
+    // in a row.
+    pub fn is_over(&self) -> bool {
+        let both_passed = self.history.len() >= 2 && self.history[self.history.len() - 2..].iter().all(|cmd| *cmd == Command::Pass);
+        both_passed || self.board.is_terminal()
+    }
+}
+
+impl Default for Game {
+    fn default() -> Game {
+        Game::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn a_game_ends_on_the_move_that_fills_the_board() {
+        // Both games fill the board, the one from seed 8 with a pass along the way.
+        for seed in [1, 8] {
+            let mut rng = Rng::new(seed);
+            let mut game = Game::new();
+            while game.board().empty_count() > 0 {
+                assert!(!game.is_over(), "seed {seed}, {} empty squares", game.board().empty_count());
+                let cmd = game.board().random_move(game.to_move(), &mut rng).map_or(Command::Pass, Command::PlayAt);
+                game.play(cmd).unwrap();
+            }
+            assert!(game.is_over(), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn redo_plays_back_what_undo_took_and_a_new_move_forgets_it() {
+        let mut game = Game::new();
+        for cmd in ["f5", "d6"] {
+            game.play(Command::parse(cmd).unwrap()).unwrap();
+        }
+        let played = game.board().to_notation(game.to_move());
+        assert_eq!(game.undo(), Some(Command::parse("d6").unwrap()));
+        game.undo();
+        assert_eq!(game.redo(), Some(Command::parse("f5").unwrap()));
+        game.redo();
+        assert_eq!(game.board().to_notation(game.to_move()), played);
+        game.undo();
+        game.play(Command::parse("f6").unwrap()).unwrap();
+        assert_eq!(game.redo(), None);
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Write;
+
+use crate::board::{Board, Player};
+use crate::error::OthelloError;
+use crate::notation::Command;
+
+fn color_tag(player: Player) -> &'static str {
+    match player {
+        Player::Black => "B",
+        Player::White => "W",
+    }
+}
+
+// The size, the rows from the top with '*' for black and 'O' for white, then the side to move,
+// as GGS writes it. Records that leave out the spaces between rows describe the same board.
+const STANDARD_START: &str = "8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *";
+
+fn is_standard_start(board: &str) -> bool {
+    board.chars().filter(|c| !c.is_whitespace()).eq(STANDARD_START.chars().filter(|c| !c.is_whitespace()))
+}
+
+fn tags(record: &str) -> Result<Vec<(&str, String)>, OthelloError> {
+    let body = record.trim().strip_prefix("(;").and_then(|rest| rest.strip_suffix(";)"))
+        .ok_or_else(|| OthelloError::InvalidGgf("record must be enclosed in '(;' and ';)'".to_string()))?;
+    let mut tags = Vec::new();
+    let mut rest = body.trim_start();
+    while !rest.is_empty() {
+        let open = rest.find('[').ok_or_else(|| OthelloError::InvalidGgf(format!("expected a tag at '{rest}'")))?;
+        let name = rest[..open].trim();
+        if name.is_empty() || !name.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(OthelloError::InvalidGgf(format!("invalid tag name '{name}'")))
+        }
+        let mut value = String::new();
+        let mut chars = rest[open + 1..].char_indices();
+        let close = loop {
+            match chars.next() {
+                Some((_, '\\')) => value.extend(chars.next().map(|(_, c)| c)),
+                Some((i, ']')) => break open + 1 + i,
+                Some((_, c)) => value.push(c),
+                None => return Err(OthelloError::InvalidGgf(format!("unterminated tag '{name}'"))),
+            }
+        };
+        tags.push((name, value));
+        rest = rest[close + 1..].trim_start();
+    }
+    Ok(tags)
+}
+
+fn parse_move(value: &str) -> Result<Command, OthelloError> {
+    let square = value.split('/').next().unwrap_or("").trim().to_ascii_lowercase();
+    if square == "pa" {
+        Ok(Command::Pass)
+    } else {
+        square.parse().map(Command::PlayAt)
+    }
+}
+
+// A zero margin keeps its sign so that games decided before the board was full round-trip.
+fn parse_result(value: &str) -> Option<Command> {
+    let score = value.split(':').next()?.trim();
+    let margin: f64 = score.parse().ok()?;
+    Some(Command::Victory(if margin > 0.0 || (margin == 0.0 && score.starts_with('+')) {
+        Some(Player::Black)
+    } else if margin < 0.0 || (margin == 0.0 && score.starts_with('-')) {
+        Some(Player::White)
+    } else {
+        None
+    }))
+}
+
+// Parses an 8x8 game starting from the standard position. Moves come out as `PlayAt`/`Pass`,
+// followed by a `Victory` when the record has a result. Other tags are ignored.
+pub fn parse(record: &str) -> Result<Vec<Command>, OthelloError> {
+    let mut moves = Vec::new();
+    let mut result = None;
+    let mut to_move = Player::Black;
+    for (name, value) in tags(record)? {
+        let player = match name {
+            "B" => Player::Black,
+            "W" => Player::White,
+            "RE" => {
+                result = parse_result(&value);
+                continue
+            }
+            "BO" if !is_standard_start(&value) => {
+                return Err(OthelloError::InvalidGgf("only the standard starting position is supported".to_string()))
+            }
+            _ => continue,
+        };
+        if player != to_move {
+            moves.push(Command::Pass);
+        }
+        moves.push(parse_move(&value)?);
+        to_move = player.opponent();
+    }
+    moves.extend(result);
+    Ok(moves)
+}
+
+// Writes the moves as a GGF record. A `Victory` command becomes the `RE` tag, with the margin
+// taken from the final position.
+pub fn serialize(moves: &[Command]) -> String {
+    let mut board = Board::new();
+    let mut player = Player::Black;
+    let mut record = format!("(;GM[Othello]TY[8]BO[{STANDARD_START}]");
+    for cmd in moves {
+        match cmd {
+            Command::PlayAt(pos) => {
+                board.play_at(*pos, player);
+                let square = Command::stringify(cmd).trim_end().to_ascii_uppercase();
+                write!(record, "{}[{square}]", color_tag(player)).expect("couldn't write to record");
+                player = player.opponent();
+            }
+            Command::Pass => {
+                write!(record, "{}[PA]", color_tag(player)).expect("couldn't write to record");
+                player = player.opponent();
+            }
+            Command::Victory(winner) => {
+                let margin = (board.player_score(Player::Black) - board.player_score(Player::White)).abs();
+                let score = match winner {
+                    Some(Player::Black) => format!("+{margin}.000"),
+                    Some(Player::White) => format!("-{margin}.000"),
+                    None => "0.000".to_string(),
+                };
+                write!(record, "RE[{score}]").expect("couldn't write to record");
+            }
+            _ => {}
+        }
+    }
+    record.push_str(";)");
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::{replay_transcript, transcript};
+
+    // A whole game as the GGS server records it, clock times after the moves, with a pass by
+    // white near the end.
+    const GGS_RECORD: &str = "(;GM[Othello]PC[GGS/os]DT[2003.12.15_13:24:03.MST]PB[alpha]PW[beta]RB[2197.72]RW[2415.33]\
+        TI[15:00//02:00]TY[8]RE[+24.000]BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]\
+        B[E6//19.13]W[F6//28.13]B[C4//16.19]W[C3//11.72]B[G6//7.15]W[D6//28.10]B[C6//14.75]W[B4//15.43]B[F5//22.43]\
+        W[G7//27.44]B[B3//27.93]W[A3//29.54]B[D3//10.82]W[C5//28.25]B[A4//15.31]W[H6//19.37]B[F4//3.07]W[A5//21.58]\
+        B[B5//16.33]W[F7//29.40]B[E7//1.50]W[D2//14.30]B[H8//0.83]W[B7//7.18]B[B6//13.55]W[C7//8.02]B[D1//29.59]\
+        W[E2//29.42]B[F1//29.71]W[G4//27.72]B[B8//13.21]W[A7//23.13]B[F8//20.77]W[G5//22.75]B[B2//12.77]W[E8//25.81]\
+        B[G8//16.86]W[C2//3.21]B[D8//11.95]W[C1//9.04]B[G3//27.18]W[F3//28.22]B[E3//21.71]W[F2//6.19]B[D7//21.88]\
+        W[H2//2.68]B[B1//2.88]W[A8//22.45]B[G2//1.64]W[A2//13.33]B[H3//13.15]W[H7//14.29]B[H1//0.26]W[H4//24.13]\
+        B[A6//16.57]W[C8//27.41]B[A1//23.46]W[G1//17.75]B[E1//17.17]W[PA]B[H5//17.00];)";
+
+    #[test]
+    fn parses_a_ggs_record() {
+        let moves = parse(GGS_RECORD).unwrap();
+        assert_eq!(moves.len(), 62);
+        assert_eq!(moves[0], Command::PlayAt("e6".parse().unwrap()));
+        assert_eq!(moves[59], Command::Pass);
+        assert_eq!(moves.iter().filter(|cmd| **cmd == Command::Pass).count(), 1);
+        assert_eq!(moves[61], Command::Victory(Some(Player::Black)));
+        let (board, to_move, _, _) = replay_transcript(&transcript(&moves[..61], Player::Black)).unwrap();
+        assert!(board.legal_moves(to_move).is_empty() && board.legal_moves(to_move.opponent()).is_empty());
+        assert_eq!((board.player_score(Player::Black), board.player_score(Player::White)), (44, 20));
+    }
+
+    #[test]
+    fn accepts_the_start_with_or_without_spaces() {
+        let unspaced = "(;GM[Othello]BO[8 ---------------------------O*------*O--------------------------- *]B[F5//1.00]W[PA];)";
+        assert_eq!(parse(unspaced).unwrap(), vec![Command::PlayAt("f5".parse().unwrap()), Command::Pass]);
+        let other = "(;GM[Othello]BO[8 -------- -------- -------- ---OO--- ---*O--- -------- -------- -------- *]B[F5];)";
+        assert!(parse(other).is_err());
+    }
+
+    #[test]
+    fn serializes_what_it_parses() {
+        let moves = parse(GGS_RECORD).unwrap();
+        let record = serialize(&moves);
+        assert!(record.contains(&format!("BO[{STANDARD_START}]")));
+        assert_eq!(parse(&record).unwrap(), moves);
+    }
+}
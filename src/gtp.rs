@@ -0,0 +1,104 @@
+use crate::board::{Board, Player, Position};
+use crate::error::OthelloError;
+use crate::search::Search;
+
+const COMMANDS: [&str; 11] = [
+    "protocol_version", "name", "version", "known_command", "list_commands", "quit",
+    "boardsize", "clear_board", "play", "genmove", "showboard",
+];
+
+fn color(arg: &str) -> Result<Player, OthelloError> {
+    match arg.to_ascii_lowercase().as_str() {
+        "b" | "black" => Ok(Player::Black),
+        "w" | "white" => Ok(Player::White),
+        _ => Err(OthelloError::InvalidColor(arg.to_string())),
+    }
+}
+
+// Answers GTP-style commands ("play black d3", "genmove white", ...) one line at a time, so
+// the engine can be driven by external GUIs and tournament harnesses over stdin/stdout.
+pub struct Engine {
+    board: Board,
+    depth: usize,
+    search: Search,
+    quit: bool,
+}
+
+impl Engine {
+    pub fn new(depth: usize) -> Engine {
+        Engine { board: Board::new(), depth, search: Search::new(), quit: false }
+    }
+
+    // True once a "quit" command has been answered.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    // The full response to one input line, "= result" or "? error" followed by a blank line,
+    // keeping any numeric id the command was prefixed with. Blank lines and comments get none.
+    pub fn respond(&mut self, line: &str) -> Option<String> {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut words = line.split_whitespace().peekable();
+        let id = match words.peek() {
+            Some(word) if word.bytes().all(|b| b.is_ascii_digit()) => words.next(),
+            Some(_) => None,
+            None => return None,
+        }.unwrap_or("");
+        let name = words.next().unwrap_or("");
+        let args: Vec<&str> = words.collect();
+        Some(match self.execute(name, &args) {
+            Ok(result) if result.is_empty() => format!("={id}\n\n"),
+            Ok(result) => format!("={id} {result}\n\n"),
+            Err(err) => format!("?{id} {err}\n\n"),
+        })
+    }
+
+    fn execute(&mut self, name: &str, args: &[&str]) -> Result<String, OthelloError> {
+        let arg = |index: usize| args.get(index).copied().ok_or_else(|| OthelloError::MissingValue(name.to_string()));
+        match name {
+            "protocol_version" => Ok("2".to_string()),
+            "name" => Ok("othello".to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => Ok(COMMANDS.contains(&arg(0)?).to_string()),
+            "list_commands" => Ok(COMMANDS.join("\n")),
+            "quit" => {
+                self.quit = true;
+                Ok(String::new())
+            }
+            "boardsize" => {
+                match arg(0)? {
+                    "8" => Ok(String::new()),
+                    size => Err(OthelloError::InvalidArgument(size.to_string())),
+                }
+            }
+            "clear_board" => {
+                self.board = Board::new();
+                Ok(String::new())
+            }
+            "play" => {
+                let player = color(arg(0)?)?;
+                match arg(1)? {
+                    vertex if vertex.eq_ignore_ascii_case("pass") => {}
+                    vertex => {
+                        let position: Position = vertex.parse()?;
+                        self.board.check_move(position, player)?;
+                        self.board.play_at(position, player);
+                    }
+                }
+                Ok(String::new())
+            }
+            "genmove" => {
+                let player = color(arg(0)?)?;
+                match self.search.choose_move(&self.board, player, self.depth) {
+                    Some((position, _)) => {
+                        self.board.play_at(position, player);
+                        Ok(position.to_string())
+                    }
+                    None => Ok("pass".to_string()),
+                }
+            }
+            "showboard" => Ok(format!("\n{}", self.board).trim_end().to_string()),
+            _ => Err(OthelloError::UnknownCommand(name.to_string())),
+        }
+    }
+}
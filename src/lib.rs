@@ -0,0 +1,33 @@
+pub mod board;
+pub mod book;
+pub mod error;
+pub mod eval;
+pub mod game;
+pub mod ggf;
+pub mod gtp;
+pub mod notation;
+pub mod opening;
+pub mod render;
+pub mod rng;
+pub mod search;
+pub mod selfplay;
+pub mod strategy;
+pub mod symmetry;
+pub mod transposition;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod zobrist;
+
+pub use board::{perft, Board, Direction, Player, Position, PLAY_DIRECTIONS};
+pub use book::Book;
+pub use error::OthelloError;
+pub use eval::{Heuristic, Phase, PhasedWeights, Weights};
+pub use game::Game;
+pub use notation::{move_table, replay_transcript, transcript, Command};
+pub use opening::{named_opening, OPENINGS};
+pub use render::Glyphs;
+pub use rng::Rng;
+pub use search::{adaptive_depth, analyze, analyze_with, choose_move, negamax, order_moves, par_choose_move, Analysis, Search, SearchStats};
+pub use strategy::Strategy;
+pub use symmetry::Symmetry;
+pub use transposition::{Bound, TranspositionTable};
@@ -1,330 +1,941 @@
-use std::fmt::Write;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Player { Black, White }
+use othello::gtp::Engine;
+use othello::selfplay::{play_match, round_robin, tune, PlayerConfig};
+use othello::{adaptive_depth, analyze_with, named_opening, replay_transcript, Board, Book, Command, Game, Glyphs, Heuristic, OthelloError, Player, Position, Rng, Search, Strategy, Weights};
 
-impl Player {
-    fn opponent(self) -> Player {
-        match self {
-            Player::Black => Player::White,
-            Player::White => Player::Black,
-        }
+#[derive(Clone, Copy)]
+struct View {
+    glyphs: Glyphs,
+    show_moves: bool,
+    show_flips: bool,
+}
+
+fn last_move(history: &[Command]) -> Option<Position> {
+    match history.last() {
+        Some(Command::PlayAt(position)) => Some(*position),
+        _ => None,
     }
+}
+
+// The discs `player` flips by playing at `position` on `board`, as "X d3 flips c3, c4", None
+// for an illegal move.
+fn flips_message(board: &Board, player: Player, position: Position) -> Option<String> {
+    let flipped: Vec<String> = board.clone().apply(position, player)?.iter().map(Position::to_string).collect();
+    Some(format!("{} {position} flips {}", player.to_char(), flipped.join(", ")))
+}
 
-    fn to_char(self) -> char {
-        match self {
-            Player::Black => 'X',
-            Player::White => 'O',
+// With `show_flips` set, announces the discs `cmd` is about to flip on `board`, to show
+// beginners what a move captured.
+fn report_flips(board: &Board, player: Player, cmd: &Command, view: View) {
+    if let (true, Command::PlayAt(position)) = (view.show_flips, cmd) {
+        if let Some(message) = flips_message(board, player, *position) {
+            println!("{message}");
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Direction { pub dx: i8, pub dy: i8 }
+// With `show_moves` set, the legal moves of `to_move` are marked; pass None when it isn't a
+// human's turn. The last move of `history`, if it wasn't a pass, is marked too.
+fn draw_board<const N: usize>(board: &Board<N>, view: View, to_move: Option<Player>, history: &[Command]) {
+    let rendering = board.render(view.glyphs).marking_last(last_move(history));
+    match to_move {
+        Some(player) if view.show_moves => println!("{}", rendering.marking_moves(player)),
+        _ => println!("{rendering}"),
+    }
+}
+
+fn arg_to_player(arg: &str) -> Result<Player, OthelloError> {
+    match arg {
+        "black" => Ok(Player::Black),
+        "white" => Ok(Player::White),
+        _=> Err(OthelloError::InvalidColor(arg.to_string()))
+    }
+}
+
+fn arg_to_depth(arg: &str) -> Result<usize, OthelloError> {
+    match arg.parse() {
+        Ok(depth) if depth > 0 => Ok(depth),
+        _ => Err(OthelloError::InvalidDepth(arg.to_string()))
+    }
+}
 
-#[derive(Debug, Clone, Copy)]
-struct Position { pub x: i8, pub y: i8 }
+fn arg_to_number<T: std::str::FromStr>(arg: &str) -> Result<T, OthelloError> {
+    arg.parse().map_err(|_| OthelloError::InvalidArgument(arg.to_string()))
+}
+
+struct Options {
+    machine_player: Player,
+    first: Player,
+    position: Option<(Board, Player)>,
+    depth: usize,
+    black_depth: Option<usize>,
+    white_depth: Option<usize>,
+    strategy: Strategy,
+    black_strategy: Option<Strategy>,
+    white_strategy: Option<Strategy>,
+    self_play: bool,
+    gtp: bool,
+    games: Option<usize>,
+    tournament: Option<Vec<PlayerConfig>>,
+    tune: Option<usize>,
+    time: Option<Duration>,
+    random_plies: Option<usize>,
+    seed: u64,
+    view: View,
+    book: Option<String>,
+    weights: Option<String>,
+    replay: Option<String>,
+    run: Option<String>,
+    solve: Option<String>,
+    batch: bool,
+    search: SearchFlags,
+    adaptive: bool,
+}
 
-impl Position {
-    fn neighbor(self, d: Direction) -> Position {
-        Position { x: self.x.saturating_add(d.dx), y: self.y.saturating_add(d.dy) }
+impl Options {
+    fn depths(&self) -> [usize; 2] {
+        [self.black_depth.unwrap_or(self.depth), self.white_depth.unwrap_or(self.depth)]
     }
 
-    fn is_valid(self) -> bool {
-        self.x >= 0 && self.x < 8 && self.y >= 0 && self.y < 8
+    fn strategies(&self) -> [Strategy; 2] {
+        [self.black_strategy.unwrap_or(self.strategy), self.white_strategy.unwrap_or(self.strategy)]
     }
 }
 
-enum Command {
-    PlayAt(Position),
-    Pass,
-    Victory(Option<Player>),
+// Settings shared by every search of a session: the game's rules and whether to trace the
+// engine's decisions to stderr.
+#[derive(Clone, Copy)]
+struct SearchFlags {
+    no_pass: bool,
+    verbose: bool,
 }
 
-static MOVES: [&str; 64] = [
-    "a1\n", "b1\n", "c1\n", "d1\n", "e1\n", "f1\n", "g1\n", "h1\n",
-    "a2\n", "b2\n", "c2\n", "d2\n", "e2\n", "f2\n", "g2\n", "h2\n",
-    "a3\n", "b3\n", "c3\n", "d3\n", "e3\n", "f3\n", "g3\n", "h3\n",
-    "a4\n", "b4\n", "c4\n", "d4\n", "e4\n", "f4\n", "g4\n", "h4\n",
-    "a5\n", "b5\n", "c5\n", "d5\n", "e5\n", "f5\n", "g5\n", "h5\n",
-    "a6\n", "b6\n", "c6\n", "d6\n", "e6\n", "f6\n", "g6\n", "h6\n",
-    "a7\n", "b7\n", "c7\n", "d7\n", "e7\n", "f7\n", "g7\n", "h7\n",
-    "a8\n", "b8\n", "c8\n", "d8\n", "e8\n", "f8\n", "g8\n", "h8\n",
-];
+impl SearchFlags {
+    fn search(self) -> Search {
+        Search::new().with_no_pass(self.no_pass).with_verbose(self.verbose)
+    }
+}
 
-impl Command {
-    fn parse(cmd: &str) -> Result<Command, ()> {
-        match cmd {
-            "black\n" => Ok(Command::Victory(Some(Player::Black))),
-            "white\n" => Ok(Command::Victory(Some(Player::White))),
-            "draw\n" => Ok(Command::Victory(None)),
-            "pass\n" => Ok(Command::Pass),
+fn option_value<'a>(args: &mut impl Iterator<Item = &'a String>, name: &str) -> Result<&'a String, OthelloError> {
+    args.next().ok_or_else(|| OthelloError::MissingValue(name.to_string()))
+}
+
+fn parse_options(args: &[String]) -> Result<Options, OthelloError> {
+    let mut options = Options {
+        machine_player: Player::Black,
+        first: Player::Black,
+        position: None,
+        depth: 8,
+        black_depth: None,
+        white_depth: None,
+        strategy: Strategy::default(),
+        black_strategy: None,
+        white_strategy: None,
+        self_play: false,
+        gtp: false,
+        games: None,
+        tournament: None,
+        tune: None,
+        time: None,
+        view: View { glyphs: Glyphs::ASCII, show_moves: false, show_flips: false },
+        book: None,
+        weights: None,
+        replay: None,
+        run: None,
+        solve: None,
+        batch: false,
+        search: SearchFlags { no_pass: false, verbose: false },
+        adaptive: false,
+        random_plies: None,
+        seed: 0,
+    };
+    let mut positional = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--self-play" => options.self_play = true,
+            "--batch" => options.batch = true,
+            "--no-pass" => options.search.no_pass = true,
+            "--verbose" => options.search.verbose = true,
+            "--adaptive" => options.adaptive = true,
+            "--human" => options.machine_player = arg_to_player(option_value(&mut args, arg)?)?.opponent(),
+            "--position" => options.position = Some(Board::from_notation(option_value(&mut args, arg)?)?),
+            "--first" => options.first = arg_to_player(option_value(&mut args, arg)?)?,
+            "--gtp" => options.gtp = true,
+            "--unicode" => options.view.glyphs = Glyphs::UNICODE,
+            "--show-moves" => options.view.show_moves = true,
+            "--show-flips" => options.view.show_flips = true,
+            "--book" => options.book = Some(option_value(&mut args, arg)?.clone()),
+            "--weights" => options.weights = Some(option_value(&mut args, arg)?.clone()),
+            "--replay" => options.replay = Some(option_value(&mut args, arg)?.clone()),
+            "--run" => options.run = Some(option_value(&mut args, arg)?.clone()),
+            "--solve" => options.solve = Some(option_value(&mut args, arg)?.clone()),
+            "--black-depth" => options.black_depth = Some(arg_to_depth(option_value(&mut args, arg)?)?),
+            "--white-depth" => options.white_depth = Some(arg_to_depth(option_value(&mut args, arg)?)?),
+            "--strategy" => options.strategy = option_value(&mut args, arg)?.parse()?,
+            "--black-strategy" => options.black_strategy = Some(option_value(&mut args, arg)?.parse()?),
+            "--white-strategy" => options.white_strategy = Some(option_value(&mut args, arg)?.parse()?),
+            "--tune" => options.tune = Some(arg_to_number(option_value(&mut args, arg)?)?),
+            "--games" => options.games = Some(arg_to_number(option_value(&mut args, arg)?)?),
+            "--time" => options.time = Some(Duration::from_secs(arg_to_number(option_value(&mut args, arg)?)?)),
+            "--random-plies" => options.random_plies = Some(arg_to_number(option_value(&mut args, arg)?)?),
+            "--tournament" => {
+                let configs = option_value(&mut args, arg)?.split(',').map(str::parse).collect::<Result<_, _>>()?;
+                options.tournament = Some(configs);
+            }
+            "--seed" => options.seed = arg_to_number(option_value(&mut args, arg)?)?,
+            _ if arg.starts_with("--") => return Err(OthelloError::InvalidArgument(arg.clone())),
             _ => {
-                match cmd.as_bytes() {
-                    [x, y, 10] => {
-                        let pos = Position { x: (x - b'a') as i8, y: (y - b'1') as i8 };
-                        if pos.is_valid() {
-                            Ok(Command::PlayAt(pos))
-                        } else {
-                            Err(())
-                        }
-                    },
-                    _ => Err(())
+                match positional {
+                    0 => options.machine_player = arg_to_player(arg)?,
+                    1 => options.depth = arg_to_depth(arg)?,
+                    _ => return Err(OthelloError::InvalidArgument(arg.clone())),
                 }
+                positional += 1;
             }
         }
     }
+    Ok(options)
+}
 
-    fn stringify(cmd: &Command) -> &str {
-        match cmd {
-            Command::PlayAt(pos @ Position { x, y }) => {
-                if !pos.is_valid() { return "pass\n" }
-                MOVES[(x + 8 * y) as usize]
-            }
-            Command::Pass => "pass\n",
-            Command::Victory(winner) => {
-                match winner {
-                    Some(Player::Black) => "black\n",
-                    Some(Player::White) => "white\n",
-                    None => "draw\n",
-                }
-            }
-        }
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Black => "black",
+        Player::White => "white",
     }
 }
 
-#[derive(Clone)]
-struct Board {
-    cells: [[u8; 8]; 2],
-}
-
-static PLAY_DIRECTIONS: [Direction; 8] = [
-    Direction {dx: -1, dy: -1}, Direction {dx: -1, dy: 0}, Direction {dx: -1, dy: 1}, 
-    Direction {dx: 0, dy: -1}, Direction {dx: 0, dy: 1},
-    Direction {dx: 1, dy: -1}, Direction {dx: 1, dy: 0}, Direction {dx: 1, dy: 1}];
-
-impl Board {
-    fn new() -> Board {
-        Board { 
-            cells: [
-                [
-                    0b00000000,
-                    0b00000000,
-                    0b00000000,
-                    0b00010000,
-                    0b00001000,
-                    0b00000000,
-                    0b00000000,
-                    0b00000000,
-                ],
-                [
-                    0b00000000,
-                    0b00000000,
-                    0b00000000,
-                    0b00001000,
-                    0b00010000,
-                    0b00000000,
-                    0b00000000,
-                    0b00000000,
-                ],
-            ]
-        }
+// The margin is the plain disc difference: squares left empty when neither side can move are
+// not awarded to the winner. A game both sides passed out while moves were left has no result.
+fn result_message(board: &Board) -> String {
+    match board.result() {
+        Some(Command::Victory(Some(winner))) => format!("{} wins by {}", player_name(winner), board.heuristic(winner)),
+        Some(_) => "draw".to_string(),
+        None => format!("stopped with moves left, X: {}, O: {}", board.player_score(Player::Black), board.player_score(Player::White)),
     }
+}
 
-    fn player_at(&self, pos: Position) -> Option<Player> {
-        let x_mask = 1 << pos.x;
-        if self.cells[Player::Black as usize][pos.y as usize] & x_mask != 0 {
-            Some(Player::Black)
-        } else if self.cells[Player::White as usize][pos.y as usize] & x_mask != 0 {
-            Some(Player::White)
-        } else {
-            None
+fn print_result(board: &Board) {
+    println!("{}", result_message(board));
+}
+
+fn or_exit<T>(result: Result<T, OthelloError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1)
         }
     }
+}
 
-    fn player_score(&self, player: Player) -> i64 {
-        self.cells[player as usize].iter().map(|byte| byte.count_ones()).sum::<u32>() as i64
-    }
+// A game from the initial position with the moves of a named opening already played.
+fn opening_game(name: &str) -> Result<Game, OthelloError> {
+    Game::from_history(named_opening(name)?, Player::Black)
+}
 
-    fn heuristic(&self, player: Player) -> i64 {
-        self.player_score(player) - self.player_score(player.opponent())
-    }
+fn load_game(path: &str) -> Result<Game, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    let (_, _, first, moves) = replay_transcript(&text)?;
+    Ok(Game::from_history(moves, first)?)
+}
 
-    fn find_bridge_candidate<'a>(&self, bridge: &'a mut [Position; 8], p: Position, d: Direction, player: Player, played: bool) -> &'a [Position] {
-        let mut length = 1usize;
-        if !p.is_valid() || (!played && self.player_at(p) != None) {
-            return &bridge[0..0]
+// Unlike a book, weights asked for are never silently replaced by the defaults.
+fn load_weights(path: &str) -> Weights {
+    let weights = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| text.parse().map_err(|err: OthelloError| err.to_string()));
+    match weights {
+        Ok(weights) => weights,
+        Err(err) => {
+            eprintln!("couldn't load weights from {path}: {err}");
+            std::process::exit(1)
         }
-        let mut current_pos = p.neighbor(d);
-        bridge[0] = current_pos;
-    
-        while current_pos.is_valid() && self.player_at(current_pos).map_or(false, |o| o == player.opponent()) {
-            current_pos = current_pos.neighbor(d);
-            bridge[length] = current_pos;
-            length += 1
+    }
+}
+
+// A book that can't be read is replaced by an empty one, and games in it that don't replay are
+// skipped, with a warning either way.
+fn load_book(path: &str) -> Book {
+    match std::fs::read_to_string(path) {
+        Ok(text) => {
+            let (book, skipped) = Book::parse(&text);
+            for err in skipped {
+                eprintln!("warning: skipping a game in {path}: {err}");
+            }
+            book
         }
-        if current_pos.is_valid() && self.player_at(current_pos).map_or(false, |o| o == player) && length > 1 {
-            return &bridge[0..length]
-        } else {
-            &bridge[0..0]
+        Err(err) => {
+            eprintln!("warning: couldn't read opening book {path}: {err}");
+            Book::new()
         }
     }
+}
+
+// The length to cut the history back to so that the human's last move is taken back, along
+// with whatever the machine or a forced pass played after it. Plies alternate from `first`.
+fn undo_len(history: &[Command], first: Player, human: Player) -> Option<usize> {
+    (0..history.len()).rev().find(|&ply| {
+        let player = if ply % 2 == 0 { first } else { first.opponent() };
+        player == human && matches!(history[ply], Command::PlayAt(_))
+    })
+}
 
-    fn set_cell(&mut self, p: Position, player: Player) {
-        self.cells[player as usize][p.y as usize] |= 1 << p.x;
-        self.cells[player.opponent() as usize][p.y as usize] &= !(1 << p.x);
+// Whether claiming `claim`, a winner or a draw, matches the board's result.
+fn claim_verdict(board: &Board, claim: Option<Player>) -> String {
+    let describe = |winner: Option<Player>| winner.map_or("a draw".to_string(), |winner| format!("a {} win", player_name(winner)));
+    match board.result() {
+        Some(Command::Victory(winner)) if winner == claim => format!("confirmed: the game is {}", describe(winner)),
+        Some(Command::Victory(winner)) => format!("rejected: the game is {}, not {}", describe(winner), describe(claim)),
+        _ => "rejected: the game isn't over, there is no result to claim yet".to_string(),
     }
+}
 
-    fn play_at(&mut self, p: Position, player: Player) -> bool {
-        let mut played = false;
-        let mut buffer = [Position{x: 0, y: 0}; 8];
-    
-        for dir in PLAY_DIRECTIONS.iter() {
-            let bridge = self.find_bridge_candidate(&mut buffer, p, *dir, player, played);
-            if bridge.len() > 0 {
-                played = true;
-                self.set_cell(p, player);
-                for position in bridge.iter() {
-                    self.set_cell(*position, player);
-                }
+fn human_play(game: &Game, depth: usize, flags: SearchFlags, input: &mut String, view: View) -> Command {
+    let (board, player, history) = (game.board(), game.to_move(), game.history());
+    let new_search = || flags.search();
+    loop {
+        input.clear();
+        println!("{}?", player.to_char());
+        // End of input, or a stdin that can't be read any more, leaves the game the same way
+        // "quit" does rather than spinning on an empty line.
+        match std::io::stdin().read_line(input) {
+            Ok(0) => return Command::Quit,
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("couldn't read input: {err}");
+                return Command::Quit
             }
         }
-        return played
-    }
-}
-
-fn negamax_ab(board: &Board, depth: usize, alpha: i64, beta: i64, player: Player) -> i64 {
-	if depth == 0 {
-		return board.heuristic(player)
-	}
-	let mut alpha = alpha;
-    let mut terminal_node = true;
-	let mut score: i64 = i32::MIN as i64;
-'forEachNodes:
-	for y in 0..8 {
-		for x in 0..8 {
-			let mut child = board.clone();
-			if child.play_at(Position{x, y}, player) {
-				terminal_node = false;
-				score = std::cmp::max(score, -negamax_ab(&child, depth-1, -beta, -alpha, player.opponent()));
-				alpha = std::cmp::max(alpha, score);
-				if alpha >= beta {
-					break 'forEachNodes
-				}
-			}
-		}
-	}
-	if terminal_node {
-		score = board.heuristic(player)
-	}
-	return score
-}
-
-fn negamax(board: &Board, depth: usize, player: Player) -> i64 {
-	negamax_ab(board, depth, i32::MIN as i64, i32::MAX as i64, player)
-}
-
-fn draw_board(board: &Board) {
-    let mut buf = "  a b c d e f g h\n".to_string();
-    for y in 0..8 {
-        buf.push((b'1'.saturating_add_signed(y)).into());
-        buf.push(' ');
-        for x in 0..8 {
-            match board.player_at(Position { x, y }) {
-                Some(player) => {
-                    buf.push(player.to_char())
+        let cmd = Command::parse(input);
+        match cmd {
+            // Malformed input, off-board coordinates and illegal moves don't cost the turn.
+            Err(err) => println!("{err}"),
+            // A claim of the result is checked against the board rather than ending the game.
+            Ok(Command::Victory(claim)) => println!("{}", claim_verdict(board, claim)),
+            Ok(Command::Pass) if board.legal_moves(player).is_empty() => return Command::Pass,
+            Ok(Command::Pass) => println!("{}", OthelloError::IllegalPass),
+            Ok(Command::PlayAt(pos)) => match board.check_move(pos, player) {
+                Ok(()) => return Command::PlayAt(pos),
+                Err(err) => println!("{err}"),
+            },
+            Ok(Command::Load(path)) => return Command::Load(path),
+            Ok(Command::Opening(name)) => return Command::Opening(name),
+            Ok(Command::Undo) => return Command::Undo,
+            Ok(Command::Redo) => return Command::Redo,
+            Ok(Command::Next(plies)) => return Command::Next(plies),
+            Ok(Command::Resign) => return Command::Resign,
+            Ok(Command::Quit) => return Command::Quit,
+            Ok(Command::Hint) => {
+                match analyze_with(board, player, depth, new_search).moves.into_iter().next() {
+                    Some((position, score, _)) => {
+                        println!("hint: {} (score {score})", position)
+                    }
+                    None => println!("hint: pass"),
+                }
+            }
+            Ok(Command::Analyze(analysis_depth)) => {
+                for (position, score, line) in analyze_with(board, player, analysis_depth.unwrap_or(depth), new_search).moves {
+                    println!("{}: {score} ({})", position, describe_line(player.opponent(), &line));
+                }
+            }
+            Ok(Command::Board) => {
+                draw_board(board, view, Some(player), history);
+                println!("X: {}, O: {}", board.player_score(Player::Black), board.player_score(Player::White));
+            }
+            Ok(Command::History) if history.is_empty() => println!("no moves yet"),
+            Ok(Command::History) => println!("{}", game.move_list()),
+            Ok(Command::Score) => {
+                let (black, white) = (board.player_score(Player::Black), board.player_score(Player::White));
+                match black.cmp(&white) {
+                    std::cmp::Ordering::Greater => println!("X: {black}, O: {white}, X leads by {}", black - white),
+                    std::cmp::Ordering::Less => println!("X: {black}, O: {white}, O leads by {}", white - black),
+                    std::cmp::Ordering::Equal => println!("X: {black}, O: {white}, even"),
+                }
+            }
+            Ok(Command::Svg(path)) => {
+                let rendering = board.render(view.glyphs).marking_last(last_move(history));
+                let svg = if view.show_moves { rendering.marking_moves(player).to_svg() } else { rendering.to_svg() };
+                match std::fs::write(&path, svg) {
+                    Ok(()) => println!("wrote the board to {path}"),
+                    Err(err) => println!("couldn't write to {path}: {err}"),
+                }
+            }
+            Ok(Command::Save(path)) => {
+                let Some(saved) = game.transcript() else {
+                    println!("games from a custom position can't be saved");
+                    continue
+                };
+                match std::fs::write(&path, saved) {
+                    Ok(()) => println!("saved {} moves to {path}", history.len()),
+                    Err(err) => println!("couldn't save to {path}: {err}"),
                 }
-                None => buf.push('.')
             }
-            buf.push(' ')
         }
-        writeln!(buf, "").expect("couldn't write to board buffer")
     }
-    println!("{buf}")
 }
 
-fn arg_to_player(arg: &str) -> Result<Player, ()> {
-    match arg {
-        "black" => Ok(Player::Black),
-        "white" => Ok(Player::White),
-        _=> Err(())
+// "O e3, X f3, ..." for the moves of `line`, the first one by `player`.
+fn describe_line(player: Player, line: &[Command]) -> String {
+    let mut player = player;
+    let mut described = Vec::new();
+    for cmd in line {
+        described.push(format!("{} {}", player.to_char(), Command::stringify(cmd).trim_end()));
+        player = player.opponent();
     }
+    described.join(", ")
 }
 
-fn human_play(board: &mut Board, player: Player, input: &mut String) -> bool {
-    input.clear();
-    println!("{}?", player.to_char());
-    std::io::stdin().read_line(input).expect("invalid string");
-    let cmd = Command::parse(&input);
-    match cmd {
-        Err(()) => {
-            println!("invalid command '{input}'");
-            true
+// On the clock the search deepens until its share of the remaining time is spent. The weaker
+// strategies play without the book.
+fn machine_play(board: &Board, player: Player, config: PlayerConfig, clock: Option<Duration>, flags: SearchFlags, book: &Book, rng: &mut Rng) -> Command {
+    let (played, report) = machine_move(board, player, config, clock, flags, book, rng);
+    if !report.is_empty() {
+        println!("{report}");
+    }
+    played
+}
+
+// The machine's move and what it has to say about it, the move and how it was found, which
+// fast-forwarding leaves unsaid.
+fn machine_move(board: &Board, player: Player, config: PlayerConfig, clock: Option<Duration>, flags: SearchFlags, book: &Book, rng: &mut Rng) -> (Command, String) {
+    // A depth that reaches the end of the game goes to the exact solver, which scores by discs.
+    let solve = config.depth >= board.empty_count() as usize;
+    let new_search = || {
+        let search = flags.search().with_heuristic(config.heuristic);
+        if solve { search.with_endgame_empties(board.empty_count()) } else { search }
+    };
+    let quick_move = match config.strategy {
+        Strategy::Random => board.random_move(player, rng),
+        Strategy::Greedy => board.greedy_move(player),
+        Strategy::Search => None,
+    };
+    if let Some(position) = quick_move {
+        return (Command::PlayAt(position), format!("{} plays {}", player.to_char(), position))
+    }
+    if let Some(position) = book.lookup(board, player) {
+        return (Command::PlayAt(position), format!("{} plays {} (book)", player.to_char(), position))
+    }
+    let (choice, stats) = match clock {
+        Some(remaining) => {
+            let (choice, stats) = board.best_move_using(new_search(), player, board.move_budget(remaining));
+            (choice.map(|position| (position, Vec::new())), stats)
+        }
+        None => {
+            let (best, stats) = new_search().best_line(board, player, config.depth);
+            (best.map(|(position, _, line)| (position, line)), stats)
         }
-        Ok(Command::Victory(winner)) => {
-            match winner {
-                Some(Player::Black) => println!("black won"),
-                Some(Player::White) => println!("white won"),
-                None => println!("it's a draw"),
+    };
+    match choice {
+        Some((position, line)) => {
+            let mut report = format!("{} plays {}", player.to_char(), position);
+            if !line.is_empty() {
+                report += &format!(" (expecting {})", describe_line(player.opponent(), &line));
             }
-            true
-        },
-        Ok(Command::Pass) => false,
-        Ok(Command::PlayAt(pos)) => {
-            !board.play_at(pos, player)
+            report += &format!("\n{} nodes, depth {}, {:?}", stats.nodes, stats.depth, stats.elapsed);
+            (Command::PlayAt(position), report)
         }
+        None => (Command::Pass, String::new()),
     }
 }
 
-fn machine_play(board: &mut Board, player: Player) -> bool {
-    let mut best_score = i64::MIN;
-    let mut best_play: Option<Position> = None;
-    for y in 0..8 {
-        for x in 0..8 {
-            let mut copy = board.clone();
-            let position = Position { x, y };
-            if copy.play_at(position, player) {
-                let score = negamax(&copy, 8, player);
-                if score > best_score {
-                    best_score = score;
-                    best_play = Some(position);
-                }
+fn random_play(board: &Board, player: Player, rng: &mut Rng) -> Command {
+    let position = board.random_move(player, rng).expect("random plies are only played with a legal move");
+    println!("{}", Command::stringify(&Command::PlayAt(position)));
+    Command::PlayAt(position)
+}
+
+// Steps through a saved game from the start, one move for each line read from stdin. Once
+// stdin runs out the rest of the game plays through without waiting.
+fn replay_game(path: &str, view: View) -> Result<(), Box<dyn std::error::Error>> {
+    let loaded = load_game(path)?;
+    let moves = loaded.history().to_vec();
+    let total = moves.len();
+    let mut game = Game::starting_with(loaded.first());
+    let mut input = String::new();
+    let mut waiting = true;
+    draw_board(game.board(), view, None, game.history());
+    for (index, cmd) in moves.into_iter().enumerate() {
+        if waiting {
+            input.clear();
+            println!("{index}/{total}, press enter for the next move");
+            waiting = std::io::stdin().read_line(&mut input)? > 0;
+        }
+        let player = game.to_move();
+        match &cmd {
+            Command::PlayAt(_) => println!("{} plays {}", player.to_char(), Command::stringify(&cmd).trim_end()),
+            _ => println!("{} passes", player.to_char()),
+        }
+        report_flips(game.board(), player, &cmd, view);
+        game.play(cmd)?;
+        draw_board(game.board(), view, None, game.history());
+        println!("X: {}, O: {}", game.board().player_score(Player::Black), game.board().player_score(Player::White));
+    }
+    if game.is_over() {
+        print_result(game.board());
+    } else {
+        println!("end of the transcript, {} to move", player_name(game.to_move()));
+    }
+    Ok(())
+}
+
+// Plays a whole transcript without reading stdin and prints where it ends, for scripts that pin
+// down known games. A transcript that doesn't replay fails on its first bad line.
+fn run_transcript(path: &str, view: View) -> Result<(), Box<dyn std::error::Error>> {
+    let game = load_game(path)?;
+    draw_board(game.board(), view, None, game.history());
+    println!("X: {}, O: {}", game.board().player_score(Player::Black), game.board().player_score(Player::White));
+    if game.is_over() {
+        print_result(game.board());
+    } else {
+        println!("end of the transcript, {} to move", player_name(game.to_move()));
+    }
+    Ok(())
+}
+
+// Positions with this many empty squares or fewer are solved to the end of the game.
+const SOLVE_EMPTIES: u32 = 14;
+
+// Prints the best move for the side to move in `notation`, "-" to read it from stdin, with its
+// exact final margin when the position is close enough to the end, or its score at `depth`.
+fn solve_position(notation: &str, depth: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = String::new();
+    let notation = if notation == "-" {
+        std::io::stdin().read_line(&mut input)?;
+        input.as_str()
+    } else {
+        notation
+    };
+    let (board, mut player) = Board::from_notation(notation)?;
+    if board.legal_moves(player).is_empty() {
+        if board.is_terminal() {
+            println!("the game is over");
+            print_result(&board);
+            return Ok(())
+        }
+        println!("{} has no legal moves and must pass", player_name(player));
+        player = player.opponent();
+    }
+    let exact = board.empty_count() <= SOLVE_EMPTIES;
+    let mut search = Search::new().with_endgame_empties(if exact { SOLVE_EMPTIES + 1 } else { 0 });
+    let (position, score) = search.choose_move(&board, player, depth).expect("the side to move has a legal move");
+    let played = format!("{} plays {}", player_name(player), position);
+    if exact {
+        println!("{played}, final margin {score:+} with best play");
+    } else {
+        println!("{played}, score {score} at depth {}", depth + 1);
+    }
+    println!("{} nodes", search.nodes());
+    Ok(())
+}
+
+// Reads a board notation per line of stdin and prints it back followed by the best move, or
+// "pass", and its score at `depth`, or "end" and the final margin for a finished game. Lines
+// that don't parse are reported on stderr by number and skipped.
+fn batch_analysis(depth: usize) {
+    for (index, line) in std::io::stdin().lock().lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("couldn't read input: {err}");
+                break
+            }
+        };
+        if line.trim().is_empty() {
+            continue
+        }
+        let (board, player) = match Board::from_notation(&line) {
+            Ok(position) => position,
+            Err(err) => {
+                eprintln!("line {}: {err}", index + 1);
+                continue
             }
+        };
+        // A fresh search per line, so one position's table entries can't sway the next.
+        let mut search = Search::new();
+        let (played, score) = match search.choose_move(&board, player, depth) {
+            Some((position, score)) => (position.to_string(), score),
+            None if board.is_terminal() => ("end".to_string(), board.heuristic(player)),
+            None => ("pass".to_string(), search.negamax(&board, depth + 1, player)),
+        };
+        println!("{} {played} {score}", line.trim());
+    }
+}
+
+fn gtp_loop(depth: usize) {
+    let mut engine = Engine::new(depth);
+    let mut stdout = std::io::stdout();
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if let Some(response) = engine.respond(&line) {
+            print!("{response}");
+            stdout.flush().expect("couldn't flush stdout");
+        }
+        if engine.has_quit() {
+            break
         }
     }
-    match best_play {
-        Some(position) => {
-            board.play_at(position, player);
-            println!("{}", Command::stringify(&Command::PlayAt(position)));
-            false
+}
+
+// The positions analysis starts out knowing: the current one, at its ply.
+fn seen_from(game: &Game) -> HashMap<u64, usize> {
+    HashMap::from([(game.board().zobrist(game.to_move()), game.history().len())])
+}
+
+// After a game with a human in it, the final position stays open for questions. Moves are
+// taken for whichever side is to move and undo takes back a single ply, so that other lines can
+// be tried from anywhere in the game; only quit leaves. Positions that moves reach are
+// remembered by hash with their ply, to point out a line that comes back to one of its own
+// earlier positions.
+fn post_game(mut game: Game, depth: usize, flags: SearchFlags, input: &mut String, view: View) {
+    println!("game over: analyze, hint, undo and moves still work on the position, quit leaves");
+    let mut seen = seen_from(&game);
+    loop {
+        match human_play(&game, depth, flags, input, view) {
+            Command::Quit => {
+                println!("goodbye");
+                return
+            }
+            Command::Load(path) => match load_game(&path) {
+                Ok(loaded) => {
+                    game = loaded;
+                    seen = seen_from(&game);
+                    println!("loaded {} moves from {path}", game.history().len());
+                    draw_board(game.board(), view, Some(game.to_move()), game.history());
+                }
+                Err(err) => println!("couldn't load {path}: {err}"),
+            },
+            Command::Opening(name) => match opening_game(&name) {
+                Ok(opening) => {
+                    game = opening;
+                    seen = seen_from(&game);
+                    println!("{}", game.move_list());
+                    draw_board(game.board(), view, Some(game.to_move()), game.history());
+                }
+                Err(err) => println!("{err}"),
+            },
+            // The positions past the new ply are no longer on the line.
+            Command::Undo => match game.undo() {
+                Some(_) => {
+                    seen.retain(|_, ply| *ply <= game.history().len());
+                    draw_board(game.board(), view, Some(game.to_move()), game.history());
+                }
+                None => println!("nothing to undo"),
+            },
+            Command::Redo => match game.redo() {
+                Some(_) => draw_board(game.board(), view, Some(game.to_move()), game.history()),
+                None => println!("nothing to redo"),
+            },
+            Command::Resign => println!("the game is already decided, quit to leave"),
+            Command::Next(_) => println!("there is no game left to fast-forward, play the moves to explore a line"),
+            _ if game.is_over() => println!("no more moves can be played here, undo to try another line"),
+            played => {
+                report_flips(game.board(), game.to_move(), &played, view);
+                game.play(played).expect("human_play only returns legal moves and passes");
+                draw_board(game.board(), view, Some(game.to_move()), game.history());
+                // A position keeps the ply it was first reached at, which undo won't prune.
+                let ply = game.history().len();
+                let at = *seen.entry(game.board().zobrist(game.to_move())).or_insert(ply);
+                if at < ply {
+                    println!("transposition: already seen this position {} moves ago", ply - at);
+                }
+                if game.is_over() {
+                    print_result(game.board());
+                }
+            }
         }
-        None => true,
     }
 }
 
+// Unless told otherwise, tournaments play this many openings per pairing, each made of this
+// many random moves, so that the games differ.
+const TOURNAMENT_OPENINGS: usize = 2;
+const TOURNAMENT_RANDOM_PLIES: usize = 4;
+
 fn main() {
-    let mut board = Board::new();
-    let mut current_player = Player::Black;
     let mut input = String::new();
-    let mut game_over = false;
-    let mut last_passed = false;
-    let mut count = 4;
-    let args: Vec<String> = std::env::args().collect();
-    let machine_player = args.get(1).map_or(Player::Black, |arg|arg_to_player(&arg).expect("invalid color"));
-    draw_board(&board);
-    while !game_over {
-        let passed = if current_player == machine_player {
-            machine_play(&mut board, current_player)
+    let mut forfeited = false;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = or_exit(parse_options(&args));
+    let mut game = match options.position.clone() {
+        Some((board, to_move)) => Game::from_position(board, to_move),
+        None => Game::starting_with(options.first),
+    };
+    let is_machine = |player: Player| options.self_play || player == options.machine_player;
+    let depths = options.depths();
+    let strategies = options.strategies();
+    let mut rng = Rng::new(options.seed);
+    if options.gtp {
+        gtp_loop(options.depth);
+        return
+    }
+    if let Some(path) = &options.replay {
+        if let Err(err) = replay_game(path, options.view) {
+            eprintln!("couldn't replay {path}: {err}");
+            std::process::exit(1)
+        }
+        return
+    }
+    if let Some(path) = &options.run {
+        if let Err(err) = run_transcript(path, options.view) {
+            eprintln!("couldn't run {path}: {err}");
+            std::process::exit(1)
+        }
+        return
+    }
+    if options.batch {
+        batch_analysis(options.depth);
+        return
+    }
+    if let Some(notation) = &options.solve {
+        if let Err(err) = solve_position(notation, options.depth) {
+            eprintln!("couldn't solve the position: {err}");
+            std::process::exit(1)
+        }
+        return
+    }
+    // Loaded weights drive the blended heuristic, which the engine then plays with.
+    let weights = options.weights.as_deref().map(load_weights);
+    let heuristic = weights.map_or_else(Heuristic::default, Heuristic::Blended);
+    // The output is itself a weights file, the progress in comments.
+    if let Some(rounds) = options.tune {
+        let start = weights.unwrap_or_default();
+        let (games, random_plies) = (options.games.unwrap_or(TOURNAMENT_OPENINGS), options.random_plies.unwrap_or(TOURNAMENT_RANDOM_PLIES));
+        let tuned = tune(start, options.depth, rounds, games, random_plies, options.seed, |step| {
+            println!("# round {}: {} {}, {} wins, {} losses, {} draws, {}", step.round, step.weight, step.value,
+                step.pairing.wins, step.pairing.losses, step.pairing.draws, if step.accepted { "kept" } else { "dropped" });
+        });
+        let blended = |weights| PlayerConfig { heuristic: Heuristic::Blended(weights), ..PlayerConfig::new(Strategy::Search, options.depth) };
+        let pairing = round_robin(&[blended(tuned), blended(start)], games, random_plies, options.seed)[0];
+        println!("# against the starting weights: {} wins, {} losses, {} draws, win rate {:.0}%", pairing.wins, pairing.losses, pairing.draws,
+            100.0 * (pairing.wins as f64 + pairing.draws as f64 / 2.0) / (2 * games) as f64);
+        print!("{tuned}");
+        return
+    }
+    if let Some(configs) = &options.tournament {
+        let configs: Vec<PlayerConfig> = configs.iter().map(|&config| match (config.heuristic, weights) {
+            (Heuristic::Blended(_), Some(weights)) => PlayerConfig { heuristic: Heuristic::Blended(weights), ..config },
+            _ => config,
+        }).collect();
+        let games = options.games.unwrap_or(TOURNAMENT_OPENINGS);
+        println!("first,second,games,wins,losses,draws,differential");
+        for pairing in round_robin(&configs, games, options.random_plies.unwrap_or(TOURNAMENT_RANDOM_PLIES), options.seed) {
+            println!("{},{},{},{},{},{},{}", configs[pairing.first], configs[pairing.second], 2 * games,
+                pairing.wins, pairing.losses, pairing.draws, pairing.differential);
+        }
+        return
+    }
+    if let Some(games) = options.games {
+        let configs = [Player::Black, Player::White].map(|player| PlayerConfig { heuristic, ..PlayerConfig::new(strategies[player as usize], depths[player as usize]) });
+        let tally = play_match(games, configs, options.random_plies.unwrap_or(0), options.seed);
+        let describe = |player: Player| match strategies[player as usize] {
+            Strategy::Search => format!("depth {}", depths[player as usize]),
+            strategy => format!("{strategy:?}").to_lowercase(),
+        };
+        println!("black ({}): {} wins, white ({}): {} wins, {} draws, average differential {:+.2}",
+            describe(Player::Black), tally.black_wins, describe(Player::White), tally.white_wins, tally.draws,
+            tally.differential as f64 / tally.games().max(1) as f64);
+        return
+    }
+    let mut clocks = options.time.map(|time| [time; 2]);
+    let book = options.book.as_deref().map_or_else(Book::new, load_book);
+    let human_to_move = |player: Player| (!is_machine(player)).then_some(player);
+    // The plies each undo took back, the last one on top, so that redo plays the same ones again.
+    let mut undone_plies = Vec::new();
+    draw_board(game.board(), options.view, human_to_move(game.to_move()), game.history());
+    let machine_config = |player: Player, board: &Board| {
+        let depth = depths[player as usize];
+        let depth = if options.adaptive { adaptive_depth(depth, board.empty_count()) } else { depth };
+        PlayerConfig { heuristic, ..PlayerConfig::new(strategies[player as usize], depth) }
+    };
+    while !game.is_over() {
+        let started = Instant::now();
+        let current_player = game.to_move();
+        // Under the no-pass rule the side that would have to pass loses on the spot.
+        let played = if game.board().legal_moves(current_player).is_empty() && options.search.no_pass {
+            println!("{} has no legal moves and loses, {} wins", current_player.to_char(), player_name(current_player.opponent()));
+            forfeited = true;
+            break
+        } else if game.board().legal_moves(current_player).is_empty() {
+            println!("{} has no legal moves, passing", current_player.to_char());
+            Command::Pass
+        } else if is_machine(current_player) && game.history().len() < options.random_plies.unwrap_or(0) {
+            random_play(game.board(), current_player, &mut rng)
+        } else if is_machine(current_player) {
+            let config = machine_config(current_player, game.board());
+            machine_play(game.board(), current_player, config, clocks.map(|clocks| clocks[current_player as usize]), options.search, &book, &mut rng)
         } else {
-            human_play(&mut board, current_player, &mut input)
+            human_play(&game, depths[current_player as usize], options.search, &mut input, options.view)
         };
-        if !passed {
-            count += 1;
+        if let Some(clocks) = clocks.as_mut() {
+            let remaining = &mut clocks[current_player as usize];
+            match remaining.checked_sub(started.elapsed()) {
+                Some(left) => *remaining = left,
+                None => {
+                    println!("{} ran out of time, {} wins", current_player.to_char(), player_name(current_player.opponent()));
+                    forfeited = true;
+                    break
+                }
+            }
         }
-        println!("X: {}, O: {}", board.player_score(Player::Black), board.player_score(Player::White));
-        game_over = (passed && last_passed) || count == 64;
-        last_passed = passed;
-        draw_board(&board);
-        current_player = current_player.opponent()
+        match &played {
+            Command::Load(path) => {
+                match load_game(path) {
+                    Ok(loaded) => {
+                        game = loaded;
+                        undone_plies.clear();
+                        println!("loaded {} moves from {path}", game.history().len());
+                        draw_board(game.board(), options.view, human_to_move(game.to_move()), game.history());
+                    }
+                    Err(err) => println!("couldn't load {path}: {err}"),
+                }
+                continue
+            }
+            Command::Opening(name) => {
+                match opening_game(name) {
+                    Ok(opening) => {
+                        game = opening;
+                        undone_plies.clear();
+                        println!("{}", game.move_list());
+                        draw_board(game.board(), options.view, human_to_move(game.to_move()), game.history());
+                    }
+                    Err(err) => println!("{err}"),
+                }
+                continue
+            }
+            Command::Undo => {
+                match undo_len(game.history(), game.first(), game.to_move()) {
+                    None => println!("nothing to undo"),
+                    Some(len) => {
+                        undone_plies.push(game.history().len() - len);
+                        while game.history().len() > len {
+                            game.undo();
+                        }
+                        draw_board(game.board(), options.view, human_to_move(game.to_move()), game.history());
+                    }
+                }
+                continue
+            }
+            // Back to the human's turn the undo started from.
+            Command::Redo => {
+                match undone_plies.pop() {
+                    None => println!("nothing to redo"),
+                    Some(plies) => {
+                        for _ in 0..plies {
+                            game.redo().expect("the game keeps the plies undo took back");
+                        }
+                        draw_board(game.board(), options.view, human_to_move(game.to_move()), game.history());
+                    }
+                }
+                continue
+            }
+            // The engine plays both sides for a while, off the clock and at each side's depth,
+            // and only the position it stops at is drawn. It stops early when the game ends or a
+            // side would have to forfeit a pass.
+            Command::Next(plies) => {
+                let mut skipped = Vec::new();
+                let first = game.to_move();
+                while skipped.len() < *plies && !game.is_over() {
+                    let player = game.to_move();
+                    let cmd = if game.board().legal_moves(player).is_empty() && options.search.no_pass {
+                        break
+                    } else if game.board().legal_moves(player).is_empty() {
+                        Command::Pass
+                    } else {
+                        machine_move(game.board(), player, machine_config(player, game.board()), None, options.search, &book, &mut rng).0
+                    };
+                    game.play(cmd.clone()).expect("the engine only returns legal moves and passes");
+                    skipped.push(cmd);
+                    undone_plies.clear();
+                }
+                println!("skipped {} plies: {}", skipped.len(), describe_line(first, &skipped));
+                println!("X: {}, O: {}", game.board().player_score(Player::Black), game.board().player_score(Player::White));
+                draw_board(game.board(), options.view, human_to_move(game.to_move()), game.history());
+                continue
+            }
+            Command::Quit => {
+                println!("goodbye");
+                println!("X: {}, O: {}", game.board().player_score(Player::Black), game.board().player_score(Player::White));
+                return
+            }
+            // A resignation ends the game by itself, so it is not counted as a pass.
+            Command::Resign => {
+                println!("{} resigns, {} wins", current_player.to_char(), player_name(current_player.opponent()));
+                forfeited = true;
+                break
+            }
+            _ => {}
+        }
+        report_flips(game.board(), current_player, &played, options.view);
+        game.play(played).expect("players only return legal moves and passes");
+        undone_plies.clear();
+        println!("X: {}, O: {}", game.board().player_score(Player::Black), game.board().player_score(Player::White));
+        if let Some([black, white]) = clocks {
+            println!("time left X: {:.1}s, O: {:.1}s", black.as_secs_f64(), white.as_secs_f64());
+        }
+        draw_board(game.board(), options.view, human_to_move(game.to_move()), game.history());
+    }
+    if !forfeited {
+        print_result(game.board());
+    }
+    if let (true, Some(transcript)) = (options.self_play, game.transcript()) {
+        print!("{transcript}");
+    }
+    if !options.self_play {
+        post_game(game, options.depth, options.search, &mut input, options.view);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(text: &str) -> Vec<Command> {
+        text.split_whitespace().map(|word| Command::parse(word).unwrap()).collect()
+    }
+
+    #[test]
+    fn undo_takes_back_the_last_human_move() {
+        // The human plays white: its reply and the machine's next move both go.
+        assert_eq!(undo_len(&moves("d3 c3 c4"), Player::Black, Player::White), Some(1));
+        // A pass after the human's move goes with it, and so does a forced pass by the human.
+        assert_eq!(undo_len(&moves("d3 c3 pass"), Player::Black, Player::White), Some(1));
+        assert_eq!(undo_len(&moves("d3 c3 c4 pass e3"), Player::Black, Player::White), Some(1));
+        assert_eq!(undo_len(&moves("d3"), Player::Black, Player::White), None);
+        // When white opens, its moves are the even plies.
+        assert_eq!(undo_len(&moves("e3 f3 e6"), Player::White, Player::White), Some(2));
+    }
+
+    #[test]
+    fn the_result_names_the_winner_and_the_margin() {
+        // Both games end in a wipeout, and the empty squares count for neither side.
+        let black_wipeout = Game::from_history(moves("d3 c3 b3 d2 e1 d6 d7 e3 f4"), Player::Black).unwrap();
+        assert_eq!(result_message(black_wipeout.board()), "black wins by 13");
+        let white_wipeout = Game::from_history(moves("d3 c3 b3 e3 f5 a3 c4 e6 f4 g4"), Player::Black).unwrap();
+        assert_eq!(result_message(white_wipeout.board()), "white wins by 14");
+        assert_eq!(result_message(&Board::new()), "stopped with moves left, X: 2, O: 2");
+    }
+
+    #[test]
+    fn the_flips_of_a_move_are_listed_by_square() {
+        let d3 = "d3".parse().unwrap();
+        assert_eq!(flips_message(&Board::new(), Player::Black, d3), Some("X d3 flips d4".to_string()));
+        let (board, player) = Board::from_notation(&format!("...OX.....O.......X.....{}X", ".".repeat(40))).unwrap();
+        assert_eq!(flips_message(&board, player, "c1".parse().unwrap()), Some("X c1 flips d1, c2".to_string()));
+        assert_eq!(flips_message(&Board::new(), Player::Black, "a1".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn claims_are_checked_against_the_result() {
+        let (black_majority, _) = Board::from_notation(&format!("{}{}X", "X".repeat(40), "O".repeat(24))).unwrap();
+        assert_eq!(claim_verdict(&black_majority, Some(Player::Black)), "confirmed: the game is a black win");
+        assert_eq!(claim_verdict(&black_majority, Some(Player::White)), "rejected: the game is a black win, not a white win");
+        assert_eq!(claim_verdict(&black_majority, None), "rejected: the game is a black win, not a draw");
+        let (tie, _) = Board::from_notation(&format!("{}X", "XO".repeat(32))).unwrap();
+        assert_eq!(claim_verdict(&tie, None), "confirmed: the game is a draw");
+        assert_eq!(claim_verdict(&Board::new(), Some(Player::Black)), "rejected: the game isn't over, there is no result to claim yet");
     }
 }
@@ -0,0 +1,270 @@
+use std::borrow::Cow;
+
+use crate::board::{Board, Player, Position};
+use crate::error::OthelloError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Command {
+    PlayAt(Position),
+    Pass,
+    Victory(Option<Player>),
+    Save(String),
+    Load(String),
+    Undo,
+    Redo,
+    Hint,
+    Resign,
+    Analyze(Option<usize>),
+    Quit,
+    Board,
+    Score,
+    History,
+    Svg(String),
+    Opening(String),
+    Next(usize),
+}
+
+// The newline-terminated coordinate of every square of a `size`x`size` board, in row-major order.
+pub fn move_table(size: usize) -> Vec<String> {
+    (0..size * size).map(|index| format!("{}\n", Position { x: (index % size) as i8, y: (index / size) as i8 })).collect()
+}
+
+impl Command {
+    // Surrounding whitespace is ignored, so both "\n" and "\r\n" line endings are accepted.
+    pub fn parse(cmd: &str) -> Result<Command, OthelloError> {
+        let cmd = cmd.trim();
+        match cmd {
+            "black" => Ok(Command::Victory(Some(Player::Black))),
+            "white" => Ok(Command::Victory(Some(Player::White))),
+            "draw" => Ok(Command::Victory(None)),
+            "pass" => Ok(Command::Pass),
+            "undo" => Ok(Command::Undo),
+            "redo" => Ok(Command::Redo),
+            "hint" => Ok(Command::Hint),
+            "resign" => Ok(Command::Resign),
+            "quit" | "exit" => Ok(Command::Quit),
+            "board" | "show" => Ok(Command::Board),
+            "score" => Ok(Command::Score),
+            "history" => Ok(Command::History),
+            "analyze" => Ok(Command::Analyze(None)),
+            _ if cmd.starts_with("analyze ") => {
+                let depth = cmd["analyze ".len()..].trim();
+                match depth.parse() {
+                    Ok(depth) if depth > 0 => Ok(Command::Analyze(Some(depth))),
+                    _ => Err(OthelloError::InvalidDepth(depth.to_string())),
+                }
+            }
+            "next" => Ok(Command::Next(1)),
+            _ if cmd.starts_with("next ") => {
+                let plies = cmd["next ".len()..].trim();
+                match plies.parse() {
+                    Ok(plies) if plies > 0 => Ok(Command::Next(plies)),
+                    _ => Err(OthelloError::InvalidArgument(plies.to_string())),
+                }
+            }
+            _ if cmd.starts_with("save ") => Ok(Command::Save(cmd["save ".len()..].trim().to_string())),
+            _ if cmd.starts_with("svg ") => Ok(Command::Svg(cmd["svg ".len()..].trim().to_string())),
+            _ if cmd.starts_with("opening ") => Ok(Command::Opening(cmd["opening ".len()..].trim().to_string())),
+            _ if cmd.starts_with("load ") => Ok(Command::Load(cmd["load ".len()..].trim().to_string())),
+            // Commands are played on the 8x8 board, so anything past h8 is outside it.
+            _ if cmd.len() == 2 => match cmd.parse::<Position>() {
+                Ok(pos) if !pos.is_valid() => Err(OthelloError::OutOfBounds(pos)),
+                parsed => parsed.map(Command::PlayAt),
+            },
+            _ => Err(OthelloError::UnknownCommand(cmd.to_string()))
+        }
+    }
+
+    pub fn stringify(cmd: &Command) -> Cow<'_, str> {
+        match cmd {
+            // Squares past the 8x8 board are written out for larger boards; only negative
+            // coordinates, which no board has, stand for a pass.
+            Command::PlayAt(pos) if pos.x < 0 || pos.y < 0 => "pass\n".into(),
+            Command::PlayAt(pos) => format!("{pos}\n").into(),
+            Command::Pass => "pass\n".into(),
+            Command::Victory(winner) => {
+                match winner {
+                    Some(Player::Black) => "black\n".into(),
+                    Some(Player::White) => "white\n".into(),
+                    None => "draw\n".into(),
+                }
+            }
+            Command::Save(path) => format!("save {path}\n").into(),
+            Command::Load(path) => format!("load {path}\n").into(),
+            Command::Svg(path) => format!("svg {path}\n").into(),
+            Command::Opening(name) => format!("opening {name}\n").into(),
+            Command::Undo => "undo\n".into(),
+            Command::Redo => "redo\n".into(),
+            Command::Hint => "hint\n".into(),
+            Command::Resign => "resign\n".into(),
+            Command::Quit => "quit\n".into(),
+            Command::Board => "board\n".into(),
+            Command::Score => "score\n".into(),
+            Command::History => "history\n".into(),
+            Command::Analyze(None) => "analyze\n".into(),
+            Command::Analyze(Some(depth)) => format!("analyze {depth}\n").into(),
+            Command::Next(plies) => format!("next {plies}\n").into(),
+        }
+    }
+}
+
+// Transcripts of games white opened start with this line, ahead of the moves.
+const WHITE_FIRST: &str = "first white";
+
+// The transcript of `history`, played from the initial position with `first` to move.
+pub fn transcript(history: &[Command], first: Player) -> String {
+    let header = (first == Player::White).then(|| Cow::from(format!("{WHITE_FIRST}\n")));
+    header.into_iter().chain(history.iter().map(Command::stringify)).collect()
+}
+
+impl Board {
+    // The position after `moves` from the initial one, the sides alternating from `first`, and
+    // the side to move then. A pass is only legal for a side without moves. Errors number the
+    // moves from 1, like the lines of a transcript.
+    pub fn from_transcript(moves: &[Command], first: Player) -> Result<(Board, Player), OthelloError> {
+        let mut board = Board::new();
+        let mut player = first;
+        for (index, cmd) in moves.iter().enumerate() {
+            let at_line = |reason| OthelloError::TranscriptLine { line: index + 1, reason: Box::new(reason) };
+            match cmd {
+                Command::PlayAt(pos) => {
+                    board.check_move(*pos, player).map_err(at_line)?;
+                    board.play_at(*pos, player);
+                }
+                Command::Pass => {
+                    if !board.legal_moves(player).is_empty() {
+                        return Err(at_line(OthelloError::IllegalPass))
+                    }
+                }
+                _ => return Err(at_line(OthelloError::UnknownCommand(Command::stringify(cmd).trim_end().to_string()))),
+            }
+            player = player.opponent();
+        }
+        Ok((board, player))
+    }
+
+    // Plays coordinates like "d3" in turn from `first`, passing for a side with no move, and
+    // returns the side to move after them. Errors number the moves from 1; the moves before the
+    // failing one stay played.
+    pub fn play_sequence(&mut self, moves: &[&str], first: Player) -> Result<Player, OthelloError> {
+        let mut player = first;
+        for (index, text) in moves.iter().enumerate() {
+            let at_move = |reason| OthelloError::SequenceMove { number: index + 1, reason: Box::new(reason) };
+            let pos: Position = text.parse().map_err(at_move)?;
+            if self.legal_moves(player).is_empty() {
+                player = player.opponent();
+            }
+            self.check_move(pos, player).map_err(at_move)?;
+            self.play_at(pos, player);
+            player = player.opponent();
+        }
+        Ok(player)
+    }
+}
+
+// Replays a transcript and returns the resulting board, the side to move, the side that moved
+// first and the moves played.
+pub fn replay_transcript(text: &str) -> Result<(Board, Player, Player, Vec<Command>), OthelloError> {
+    let mut lines = text.lines().enumerate().peekable();
+    let header = lines.next_if(|(_, line)| line.trim() == WHITE_FIRST).is_some();
+    let first = if header { Player::White } else { Player::Black };
+    let moves = lines
+        .map(|(index, line)| Command::parse(line)
+            .map_err(|reason| OthelloError::TranscriptLine { line: index + 1, reason: Box::new(reason) }))
+        .collect::<Result<Vec<_>, _>>()?;
+    // from_transcript numbers the moves, which come a line later behind the header.
+    let (board, player) = Board::from_transcript(&moves, first).map_err(|err| match err {
+        OthelloError::TranscriptLine { line, reason } => OthelloError::TranscriptLine { line: line + usize::from(header), reason },
+        err => err,
+    })?;
+    Ok((board, player, first, moves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A whole game where each side plays its `step * ply`-th legal move, wrapping around.
+    fn play_out(step: usize) -> (Board, Player, Vec<Command>) {
+        let (mut board, mut player) = (Board::new(), Player::Black);
+        let mut history = Vec::new();
+        while !board.legal_moves(player).is_empty() || !board.legal_moves(player.opponent()).is_empty() {
+            let moves = board.legal_moves(player);
+            if moves.is_empty() {
+                history.push(Command::Pass);
+            } else {
+                let pos = moves[step * history.len() % moves.len()];
+                board.play_at(pos, player);
+                history.push(Command::PlayAt(pos));
+            }
+            player = player.opponent();
+        }
+        (board, player, history)
+    }
+
+    #[test]
+    fn a_saved_transcript_loads_back_into_the_same_game() {
+        // Steps 1 and 10 play games with passes in them.
+        for step in [1, 4, 10] {
+            let (board, player, history) = play_out(step);
+            let (loaded, to_move, first, moves) = replay_transcript(&transcript(&history, Player::Black)).unwrap();
+            for side in [Player::Black, Player::White] {
+                assert_eq!(loaded.discs(side), board.discs(side), "step {step}");
+            }
+            assert_eq!((to_move, first, moves), (player, Player::Black, history));
+        }
+    }
+
+    #[test]
+    fn a_move_past_h8_is_outside_the_board() {
+        assert!(matches!(Command::parse("i3\n"), Err(OthelloError::OutOfBounds(Position { x: 8, y: 2 }))));
+        assert_eq!(Command::parse("h8\n").unwrap(), Command::PlayAt(Position { x: 7, y: 7 }));
+    }
+
+    #[test]
+    fn coordinates_parse_whatever_their_case_and_line_ending() {
+        let d3 = Command::PlayAt(Position { x: 3, y: 2 });
+        for text in ["d3", "D3", "d3 ", "d3\n", "d3\r\n"] {
+            assert_eq!(Command::parse(text).unwrap(), d3, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn a_game_white_opened_says_so_in_its_transcript() {
+        let moves = vec![Command::parse("e3").unwrap(), Command::parse("f3").unwrap()];
+        let text = transcript(&moves, Player::White);
+        assert_eq!(text, "first white\ne3\nf3\n");
+        let (board, to_move, first, loaded) = replay_transcript(&text).unwrap();
+        assert_eq!((to_move, first, &loaded), (Player::White, Player::White, &moves));
+        assert_eq!(board.discs(Player::White), Board::from_transcript(&moves, Player::White).unwrap().0.discs(Player::White));
+        // Errors still give the line of the file, header included.
+        assert!(matches!(replay_transcript("first white\ne3\ne3\n"), Err(OthelloError::TranscriptLine { line: 3, .. })));
+    }
+
+    #[test]
+    fn a_replayed_opening_matches_its_notation() {
+        let moves: Vec<Command> = ["d3", "c5", "e6"].iter().map(|text| Command::parse(text).unwrap()).collect();
+        let (board, player) = Board::from_transcript(&moves, Player::Black).unwrap();
+        let rows = ["........", "........", "...X....", "...XX...", "..OOX...", "....X...", "........", "........"];
+        assert_eq!(board.to_notation(player), format!("{}O", rows.concat()));
+    }
+
+    #[test]
+    fn a_pass_is_refused_while_the_side_has_moves() {
+        let moves = [Command::parse("d3").unwrap(), Command::Pass];
+        let replayed = Board::from_transcript(&moves, Player::Black);
+        assert!(matches!(replayed, Err(OthelloError::TranscriptLine { line: 2, reason }) if matches!(*reason, OthelloError::IllegalPass)));
+    }
+
+    #[test]
+    fn the_move_table_names_every_square_of_larger_boards() {
+        let table = move_table(10);
+        assert_eq!(table.len(), 100);
+        assert_eq!((table[0].as_str(), table[9].as_str(), table[90].as_str(), table[99].as_str()), ("a1\n", "j1\n", "a10\n", "j10\n"));
+        for text in &table {
+            let position: Position = text.parse().unwrap();
+            assert_eq!(Command::stringify(&Command::PlayAt(position)), text.as_str());
+        }
+    }
+}
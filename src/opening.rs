@@ -0,0 +1,38 @@
+use crate::error::OthelloError;
+use crate::notation::Command;
+
+// Named openings, as the moves that reach them from the initial position, black first.
+pub const OPENINGS: [(&str, &str); 9] = [
+    ("perpendicular", "f5 d6"),
+    ("diagonal", "f5 f6"),
+    ("parallel", "f5 f4"),
+    ("tiger", "f5 d6 c3 d3 c4"),
+    ("cow", "f5 d6 c5 f4 e3"),
+    ("chimney", "f5 d6 c5 f4 e3 f6"),
+    ("rose", "f5 d6 c5 f4 e3 f6 g5 e6 e7"),
+    ("rabbit", "f5 f6 e6 f4 e3"),
+    ("heath", "f5 f6 e6 f4 g5"),
+];
+
+// The moves of the opening called `name`, whatever its case.
+pub fn named_opening(name: &str) -> Result<Vec<Command>, OthelloError> {
+    let Some((_, moves)) = OPENINGS.iter().find(|(opening, _)| opening.eq_ignore_ascii_case(name.trim())) else {
+        return Err(OthelloError::UnknownOpening(name.trim().to_string()))
+    };
+    Ok(moves.split_whitespace().map(|cmd| Command::parse(cmd).expect("openings are made of coordinates")).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, Player};
+
+    #[test]
+    fn every_opening_replays_from_the_initial_position() {
+        for (name, _) in OPENINGS {
+            assert!(Board::from_transcript(&named_opening(name).unwrap(), Player::Black).is_ok(), "{name}");
+        }
+        assert_eq!(named_opening(" Tiger ").unwrap().len(), 5);
+        assert!(matches!(named_opening("dragon"), Err(OthelloError::UnknownOpening(_))));
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::{self, Write};
+
+use crate::board::{Board, Player, Position};
+
+// The characters a board is drawn with. Coordinates are unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyphs {
+    pub black: char,
+    pub white: char,
+    pub empty: char,
+    pub legal: char,
+}
+
+impl Glyphs {
+    pub const ASCII: Glyphs = Glyphs { black: 'X', white: 'O', empty: '.', legal: '*' };
+    pub const UNICODE: Glyphs = Glyphs { black: '\u{25cf}', white: '\u{25cb}', empty: '\u{00b7}', legal: '*' };
+
+    pub fn disc(&self, player: Player) -> char {
+        match player {
+            Player::Black => self.black,
+            Player::White => self.white,
+        }
+    }
+}
+
+impl Default for Glyphs {
+    fn default() -> Glyphs {
+        Glyphs::ASCII
+    }
+}
+
+pub struct Rendering<'a, const N: usize> {
+    board: &'a Board<N>,
+    glyphs: Glyphs,
+    marked: Option<Player>,
+    last: Option<Position>,
+}
+
+impl<const N: usize> Board<N> {
+    pub fn render(&self, glyphs: Glyphs) -> Rendering<'_, N> {
+        Rendering { board: self, glyphs, marked: None, last: None }
+    }
+}
+
+impl<const N: usize> Rendering<'_, N> {
+    // Draws the squares where `player` can play with the `legal` glyph.
+    pub fn marking_moves(self, player: Player) -> Self {
+        Rendering { marked: Some(player), ..self }
+    }
+
+    // Puts the square of the last move between parentheses, drawn where the spaces around it
+    // would be so the columns stay aligned.
+    pub fn marking_last(self, last: Option<Position>) -> Self {
+        Rendering { last, ..self }
+    }
+}
+
+const SQUARE: usize = 40;
+const MARGIN: usize = 20;
+
+impl<const N: usize> Rendering<'_, N> {
+    // An SVG document of the board with coordinates along the top and left edges. Discs are
+    // circles; legal moves, when marked, are small grey dots and the last move is outlined.
+    // Glyphs don't apply.
+    pub fn to_svg(&self) -> String {
+        let size = MARGIN + N * SQUARE;
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\" font-family=\"sans-serif\" font-size=\"12\">\n");
+        let board = N * SQUARE;
+        writeln!(svg, "<rect x=\"{MARGIN}\" y=\"{MARGIN}\" width=\"{board}\" height=\"{board}\" fill=\"#2e8b57\" stroke=\"black\"/>").expect("couldn't write to string");
+        let legal = self.marked.map_or(Vec::new(), |player| self.board.legal_moves(player));
+        for i in 0..N {
+            let center = MARGIN + i * SQUARE + SQUARE / 2;
+            let line = MARGIN + i * SQUARE;
+            writeln!(svg, "<text x=\"{center}\" y=\"14\" text-anchor=\"middle\">{}</text>", Position::column_letter(i as i8)).expect("couldn't write to string");
+            writeln!(svg, "<text x=\"10\" y=\"{}\" text-anchor=\"middle\">{}</text>", center + 4, i + 1).expect("couldn't write to string");
+            if i > 0 {
+                writeln!(svg, "<line x1=\"{line}\" y1=\"{MARGIN}\" x2=\"{line}\" y2=\"{size}\" stroke=\"black\"/>").expect("couldn't write to string");
+                writeln!(svg, "<line x1=\"{MARGIN}\" y1=\"{line}\" x2=\"{size}\" y2=\"{line}\" stroke=\"black\"/>").expect("couldn't write to string");
+            }
+        }
+        for (pos, disc) in self.board.cells() {
+            let (cx, cy) = (MARGIN + pos.x as usize * SQUARE + SQUARE / 2, MARGIN + pos.y as usize * SQUARE + SQUARE / 2);
+            match disc {
+                Some(player) => {
+                    let fill = match player {
+                        Player::Black => "black",
+                        Player::White => "white",
+                    };
+                    writeln!(svg, "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{}\" fill=\"{fill}\" stroke=\"black\"/>", SQUARE / 2 - 4).expect("couldn't write to string");
+                }
+                None if legal.contains(&pos) => {
+                    writeln!(svg, "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"4\" fill=\"#555\"/>").expect("couldn't write to string");
+                }
+                None => {}
+            }
+            if self.last == Some(pos) {
+                writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"red\" stroke-width=\"2\"/>",
+                    cx - SQUARE / 2 + 1, cy - SQUARE / 2 + 1, SQUARE - 2, SQUARE - 2).expect("couldn't write to string");
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl<const N: usize> Board<N> {
+    pub fn to_svg(&self) -> String {
+        self.render(Glyphs::default()).to_svg()
+    }
+}
+
+// The column letters, then one numbered row per line, each line newline-terminated.
+impl<const N: usize> fmt::Display for Rendering<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = N.to_string().len();
+        let legal = self.marked.map_or(Vec::new(), |player| self.board.legal_moves(player));
+        let columns: Vec<String> = (0..N).map(|x| Position::column_letter(x as i8).to_string()).collect();
+        writeln!(f, "{:width$} {}", "", columns.join(" "))?;
+        let mut last_column = None;
+        for (pos, disc) in self.board.cells() {
+            let (x, y) = (pos.x as usize, pos.y as usize);
+            if x == 0 {
+                write!(f, "{:<width$}", y + 1)?;
+                last_column = self.last.filter(|last| last.y == pos.y).map(|last| last.x as usize);
+            }
+            let separator = match last_column {
+                Some(column) if column == x => '(',
+                Some(column) if column + 1 == x => ')',
+                _ => ' ',
+            };
+            let glyph = match disc {
+                Some(player) => self.glyphs.disc(player),
+                None if legal.contains(&pos) => self.glyphs.legal,
+                None => self.glyphs.empty,
+            };
+            write!(f, "{separator}{glyph}")?;
+            if x == N - 1 {
+                writeln!(f, "{}", if last_column == Some(N - 1) { ')' } else { ' ' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Display for Board<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(Glyphs::ASCII).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marking_moves_draws_each_legal_square() {
+        let board = Board::new();
+        let marked = board.render(Glyphs::ASCII).marking_moves(Player::Black).to_string();
+        assert_eq!(marked.matches('*').count(), 4, "{marked}");
+        assert_eq!(board.render(Glyphs::ASCII).to_string().matches('*').count(), 0);
+        assert_eq!(marked.lines().nth(3), Some("3 . . . * . . . . "));
+    }
+
+    #[test]
+    fn svg_draws_a_circle_per_disc_and_marked_move() {
+        // After d3 black has four discs against one, and white can answer at c3, e3 or c5.
+        let mut board = Board::new();
+        board.play_at("d3".parse().unwrap(), Player::Black);
+        assert_eq!(board.legal_moves(Player::White).len(), 3);
+        assert_eq!(board.to_svg().matches("<circle").count(), 5);
+        let marked = board.render(Glyphs::ASCII).marking_moves(Player::White).to_svg();
+        assert_eq!(marked.matches("<circle").count(), 8);
+        assert_eq!(marked.matches("r=\"4\"").count(), 3);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::zobrist::splitmix64;
+
+// A small splitmix64 generator, so a seed always reproduces the same games.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    // An independent generator for the `index`-th of several games played from one seed.
+    pub fn for_game(seed: u64, index: usize) -> Rng {
+        Rng::new(splitmix64(seed ^ (index as u64).wrapping_mul(0x9e3779b97f4a7c15)).1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let (state, value) = splitmix64(self.state);
+        self.state = state;
+        value
+    }
+
+    // A uniform integer in `0..bound`; `bound` must not be zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+}
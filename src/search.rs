@@ -0,0 +1,817 @@
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::board::{Board, Player, Position};
+use crate::eval::{Heuristic, POSITIONAL_WEIGHTS};
+use crate::notation::Command;
+use crate::symmetry::Symmetry;
+use crate::transposition::{Bound, Entry, TranspositionTable};
+
+// Corners first, then the other squares by decreasing positional weight.
+pub fn order_moves(moves: &mut [Position]) {
+	moves.sort_by_key(|p| std::cmp::Reverse(POSITIONAL_WEIGHTS[(p.x + 8 * p.y) as usize]));
+}
+
+// What a search did: the positions it visited, how many plies deep it looked from the root and
+// how long it took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+	pub nodes: u64,
+	pub depth: usize,
+	pub elapsed: Duration,
+}
+
+// The legal moves, best first, each with its score and the continuation the search expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+	pub moves: Vec<(Position, i64, Vec<Command>)>,
+	pub stats: SearchStats,
+}
+
+pub struct Search {
+	tt: TranspositionTable,
+	nodes: u64,
+	deadline: Option<Instant>,
+	timed_out: bool,
+	heuristic: Heuristic,
+	endgame_empties: u32,
+	pvs: bool,
+	symmetry: bool,
+	ordering: bool,
+	mtdf: bool,
+	no_pass: bool,
+	verbose: bool,
+	// Two moves per number of empty squares, which stands for the ply, that last caused a cutoff.
+	killers: [[Option<Position>; 2]; 65],
+	// How much cutoffs each square has produced, per player.
+	history: [[u64; 64]; 2],
+}
+
+const DEFAULT_ENDGAME_EMPTIES: u32 = 10;
+
+// Half-width of the window around the previous depth's score in iterative deepening.
+const ASPIRATION_WINDOW: i64 = 4;
+
+// The score of a side that has to pass under the no-pass rule, below any evaluation.
+const NO_MOVE_LOSS: i64 = -(i32::MAX as i64) / 2;
+
+// Transposition entries from the exact solver, which are never mixed with heuristic scores.
+const EXACT_DEPTH: usize = usize::MAX;
+
+impl Search {
+	pub fn new() -> Search {
+		Search { tt: TranspositionTable::new(), nodes: 0, deadline: None, timed_out: false, heuristic: Heuristic::default(), endgame_empties: DEFAULT_ENDGAME_EMPTIES, pvs: true, symmetry: false, ordering: true, mtdf: false, no_pass: false, verbose: false, killers: [[None; 2]; 65], history: [[0; 64]; 2] }
+	}
+
+	pub fn with_deadline(self, deadline: Instant) -> Search {
+		Search { deadline: Some(deadline), ..self }
+	}
+
+	pub fn with_heuristic(self, heuristic: Heuristic) -> Search {
+		Search { heuristic, ..self }
+	}
+
+	// Positions with fewer empty squares than this are solved exactly; 0 disables the solver.
+	pub fn with_endgame_empties(self, endgame_empties: u32) -> Search {
+		Search { endgame_empties, ..self }
+	}
+
+	// Principal-variation search: moves after the first are tried with a null window and only
+	// searched again in full when they beat it. Scores are the same as plain alpha-beta; it is
+	// on by default.
+	pub fn with_pvs(self, pvs: bool) -> Search {
+		Search { pvs, ..self }
+	}
+
+	// Tries killer moves, then moves by their cutoff history, before the static square order.
+	// On by default; turning it off leaves `order_moves` alone.
+	pub fn with_dynamic_ordering(self, ordering: bool) -> Search {
+		Search { ordering, ..self }
+	}
+
+	// Makes `negamax`, and so `choose_move`, converge on the score with MTD(f) instead of a
+	// single full-window search. Off by default.
+	pub fn with_mtdf(self, mtdf: bool) -> Search {
+		Search { mtdf, ..self }
+	}
+
+	// The variant where a side that would have to pass loses instead. A game neither side can
+	// go on with still ends on the disc count. Off by default.
+	pub fn with_no_pass(self, no_pass: bool) -> Search {
+		Search { no_pass, ..self }
+	}
+
+	// Traces the decisions at the root to stderr: the score and expected line of each move,
+	// cutoffs with the bound that caused them, and aspiration windows searched again. Nothing
+	// is formatted when off, the default.
+	pub fn with_verbose(self, verbose: bool) -> Search {
+		Search { verbose, ..self }
+	}
+
+	fn order(&self, moves: &mut [Position], player: Player, ply: usize) {
+		if !self.ordering {
+			return order_moves(moves)
+		}
+		let killers = self.killers[ply];
+		let history = &self.history[player as usize];
+		moves.sort_by_key(|&p| {
+			let index = (p.x + 8 * p.y) as usize;
+			let killer = killers.iter().position(|&killer| killer == Some(p)).map_or(2, |slot| slot);
+			(killer, std::cmp::Reverse(POSITIONAL_WEIGHTS[index]), std::cmp::Reverse(history[index]))
+		});
+	}
+
+	fn record_cutoff(&mut self, position: Position, player: Player, ply: usize, depth: usize) {
+		let killers = &mut self.killers[ply];
+		if killers[0] != Some(position) {
+			killers[1] = killers[0];
+			killers[0] = Some(position);
+		}
+		self.history[player as usize][(position.x + 8 * position.y) as usize] += (depth * depth) as u64;
+	}
+
+	// Keys the table by the canonical form of each position, so symmetric positions share an
+	// entry. Finding the canonical form costs more than the extra hits save in most positions,
+	// so it is off by default.
+	pub fn with_symmetry(self, symmetry: bool) -> Search {
+		Search { symmetry, ..self }
+	}
+
+	// The table key of a position and the symmetry its best move is stored under.
+	fn table_key(&self, board: &Board, player: Player) -> (u64, Symmetry) {
+		if self.symmetry {
+			let (canonical, symmetry) = board.canonical_with_symmetry();
+			(canonical.zobrist(player), symmetry)
+		} else {
+			(board.zobrist(player), Symmetry::default())
+		}
+	}
+
+	// Positions visited since the search was created.
+	pub fn nodes(&self) -> u64 {
+		self.nodes
+	}
+
+	pub fn timed_out(&self) -> bool {
+		self.timed_out
+	}
+
+	fn out_of_time(&mut self) -> bool {
+		if !self.timed_out {
+			self.timed_out = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+		}
+		self.timed_out
+	}
+
+	// Positions with fewer than `endgame_empties` empty squares, at the root or anywhere below
+	// it, are solved exactly instead.
+	pub fn negamax_ab(&mut self, board: &Board, depth: usize, alpha: i64, beta: i64, player: Player) -> i64 {
+		self.alpha_beta(board, depth, alpha, beta, player, false)
+	}
+
+	// Searches to the end of the game and returns the final disc differential for `player`.
+	pub fn solve(&mut self, board: &Board, alpha: i64, beta: i64, player: Player) -> i64 {
+		self.alpha_beta(board, 0, alpha, beta, player, true)
+	}
+
+	// With `exact` set the subtree is searched to the end of the game and `depth` is unused. It
+	// is set from the first node with fewer than `endgame_empties` empty squares down.
+	fn alpha_beta(&mut self, board: &Board, depth: usize, alpha: i64, beta: i64, player: Player, exact: bool) -> i64 {
+		if self.out_of_time() {
+			return 0
+		}
+		self.nodes += 1;
+		let exact = exact || board.empty_count() < self.endgame_empties;
+		if depth == 0 && !exact {
+			return self.heuristic.evaluate(board, player)
+		}
+		let mut alpha = alpha;
+		let mut beta = beta;
+		let alpha_orig = alpha;
+		let (key, symmetry) = self.table_key(board, player);
+		let stored_depth = if exact { EXACT_DEPTH } else { depth };
+		if let Some(entry) = self.tt.probe(key) {
+			let usable = if exact { entry.depth == EXACT_DEPTH } else { entry.depth >= depth && entry.depth != EXACT_DEPTH };
+			if usable {
+				match entry.bound {
+					Bound::Exact => return entry.score,
+					Bound::Lower => alpha = std::cmp::max(alpha, entry.score),
+					Bound::Upper => beta = std::cmp::min(beta, entry.score),
+				}
+				if alpha >= beta {
+					return entry.score
+				}
+			}
+		}
+		let mut moves = board.legal_moves(player);
+		if moves.is_empty() {
+			if board.is_terminal() {
+				return if exact { board.heuristic(player) } else { self.heuristic.evaluate(board, player) }
+			}
+			if self.no_pass {
+				return NO_MOVE_LOSS
+			}
+			return -self.alpha_beta(board, depth, -beta, -alpha, player.opponent(), exact)
+		}
+		let ply = board.empty_count() as usize;
+		self.order(&mut moves, player, ply);
+		let child_depth = if exact { 0 } else { depth - 1 };
+		let mut score: i64 = i32::MIN as i64;
+		let mut best = None;
+		for (index, position) in moves.into_iter().enumerate() {
+			let mut child = board.clone();
+			child.play_at(position, player);
+			let mut child_score;
+			if self.pvs && index > 0 {
+				child_score = -self.alpha_beta(&child, child_depth, -alpha - 1, -alpha, player.opponent(), exact);
+				if child_score > alpha && child_score < beta && !self.timed_out {
+					child_score = -self.alpha_beta(&child, child_depth, -beta, -alpha, player.opponent(), exact);
+				}
+			} else {
+				child_score = -self.alpha_beta(&child, child_depth, -beta, -alpha, player.opponent(), exact);
+			}
+			if self.timed_out {
+				return 0
+			}
+			if child_score > score {
+				score = child_score;
+				best = Some(position);
+			}
+			alpha = std::cmp::max(alpha, score);
+			if alpha >= beta {
+				self.record_cutoff(position, player, ply, if exact { ply } else { depth });
+				break
+			}
+		}
+		let bound = if score <= alpha_orig {
+			Bound::Upper
+		} else if score >= beta {
+			Bound::Lower
+		} else {
+			Bound::Exact
+		};
+		let best = if bound == Bound::Upper { None } else { best.map(|position| symmetry.apply(position, 8)) };
+		self.tt.store(Entry { key, depth: stored_depth, score, bound, best });
+		score
+	}
+
+	// The line the last search expects from `board`, read back from the exact entries of the
+	// table, with `max_moves` moves at most. Forced passes appear as `Command::Pass`.
+	pub fn principal_variation(&self, board: &Board, player: Player, max_moves: usize) -> Vec<Command> {
+		let mut line = Vec::new();
+		let mut board = board.clone();
+		let mut player = player;
+		let mut played = 0;
+		while played < max_moves {
+			if board.legal_moves(player).is_empty() {
+				if board.is_terminal() {
+					break
+				}
+				line.push(Command::Pass);
+				player = player.opponent();
+				continue
+			}
+			let (key, symmetry) = self.table_key(&board, player);
+			let best = self.tt.probe(key)
+				.filter(|entry| entry.bound == Bound::Exact)
+				.and_then(|entry| entry.best)
+				.map(|position| symmetry.inverse().apply(position, 8));
+			match best {
+				Some(position) if board.play_at(position, player) => line.push(Command::PlayAt(position)),
+				_ => break,
+			}
+			player = player.opponent();
+			played += 1;
+		}
+		if line.last() == Some(&Command::Pass) {
+			line.pop();
+		}
+		line
+	}
+
+	// The best root move and its score with alpha and beta narrowed to the window. The score is
+	// exact only when it lands strictly inside it. None when the deadline passed.
+	fn search_root(&mut self, board: &Board, moves: &[Position], depth: usize, alpha: i64, beta: i64, player: Player) -> Option<(Position, i64)> {
+		let mut alpha = alpha;
+		let mut best = (moves[0], i64::MIN);
+		for &position in moves {
+			let mut child = board.clone();
+			child.play_at(position, player);
+			let score = -self.negamax_ab(&child, depth - 1, -beta, -alpha, player.opponent());
+			if self.timed_out {
+				return None
+			}
+			// Scores outside the window are only bounds, with no line known after them.
+			if self.verbose && score <= alpha {
+				eprintln!("search: depth {depth}, {position} scores at most {score}");
+			} else if self.verbose && score >= beta {
+				eprintln!("search: depth {depth}, {position} scores at least {score}");
+			} else if self.verbose {
+				let line: Vec<String> = self.principal_variation(&child, player.opponent(), depth - 1).iter().map(|cmd| Command::stringify(cmd).trim_end().to_string()).collect();
+				eprintln!("search: depth {depth}, {position} scores {score}, expecting {}", line.join(" "));
+			}
+			if score > best.1 {
+				best = (position, score);
+			}
+			alpha = std::cmp::max(alpha, score);
+			if alpha >= beta {
+				if self.verbose {
+					eprintln!("search: depth {depth}, {position} cuts off at beta {beta}");
+				}
+				break
+			}
+		}
+		Some(best)
+	}
+
+	// Searches 1, 2, ... up to `max_depth` plies from the root, the root move included, each
+	// depth within a window around the previous depth's score, widened and searched again when
+	// the score falls outside it. Returns the best move and score of the deepest depth that
+	// completed before the deadline, and that depth.
+	pub fn iterative_deepening(&mut self, board: &Board, player: Player, max_depth: usize) -> Option<(Position, i64, usize)> {
+		let moves = board.legal_moves(player);
+		if moves.is_empty() {
+			return None
+		}
+		let mut best = None;
+		for depth in 1..=max_depth {
+			let (mut alpha, mut beta) = match best {
+				Some((_, score, _)) => (score - ASPIRATION_WINDOW, score + ASPIRATION_WINDOW),
+				None => (i32::MIN as i64, i32::MAX as i64),
+			};
+			loop {
+				let Some((position, score)) = self.search_root(board, &moves, depth, alpha, beta, player) else {
+					return best
+				};
+				if self.verbose && !(alpha < score && score < beta) {
+					eprintln!("search: depth {depth}, score {score} outside the window [{alpha}, {beta}], searching again");
+				}
+				if score <= alpha && alpha > i32::MIN as i64 {
+					alpha = i32::MIN as i64;
+				} else if score >= beta && beta < i32::MAX as i64 {
+					beta = i32::MAX as i64;
+				} else {
+					best = Some((position, score, depth));
+					break
+				}
+			}
+		}
+		best
+	}
+
+	pub fn negamax(&mut self, board: &Board, depth: usize, player: Player) -> i64 {
+		if self.mtdf {
+			let guess = self.heuristic.evaluate(board, player);
+			return self.mtdf(board, depth, guess, player)
+		}
+		self.negamax_ab(board, depth, i32::MIN as i64, i32::MAX as i64, player)
+	}
+
+	// MTD(f): null-window searches around `guess`, each one moving the lower or the upper bound
+	// on the score, until they meet on the same score as `negamax`. The table keeps the work of
+	// one pass for the next, so the better the guess the fewer passes.
+	pub fn mtdf(&mut self, board: &Board, depth: usize, guess: i64, player: Player) -> i64 {
+		let (mut lower, mut upper) = (i32::MIN as i64, i32::MAX as i64);
+		let mut score = guess;
+		while lower < upper {
+			let beta = if score == lower { score + 1 } else { score };
+			score = self.negamax_ab(board, depth, beta - 1, beta, player);
+			if self.timed_out {
+				return 0
+			}
+			if score < beta {
+				upper = score;
+			} else {
+				lower = score;
+			}
+		}
+		score
+	}
+
+	// The best move and its score from one search of `depth` plies, the move included, with the
+	// bound of the moves already searched narrowing the window of the next. Picks the same move
+	// as `choose_move` one ply shallower, at a fraction of the work.
+	pub fn negamax_root(&mut self, board: &Board, depth: usize, player: Player) -> Option<(Position, i64)> {
+		let moves = board.legal_moves(player);
+		if moves.is_empty() {
+			return None
+		}
+		self.search_root(board, &moves, depth, i32::MIN as i64, i32::MAX as i64, player)
+	}
+
+	// `negamax_root` searching `depth` plies past the move, with the line expected after it.
+	pub fn best_line(&mut self, board: &Board, player: Player, depth: usize) -> (Option<(Position, i64, Vec<Command>)>, SearchStats) {
+		let started = Instant::now();
+		let nodes = self.nodes;
+		let best = self.negamax_root(board, depth + 1, player).map(|(position, score)| {
+			let mut child = board.clone();
+			child.play_at(position, player);
+			(position, score, self.principal_variation(&child, player.opponent(), depth))
+		});
+		(best, SearchStats { nodes: self.nodes - nodes, depth: reported_depth(board, depth), elapsed: started.elapsed() })
+	}
+
+	// Scores each legal move by searching `depth` plies past it and returns the best one.
+	pub fn choose_move(&mut self, board: &Board, player: Player, depth: usize) -> Option<(Position, i64)> {
+		let mut best: Option<(Position, i64)> = None;
+		for position in board.legal_moves(player) {
+			let mut copy = board.clone();
+			copy.play_at(position, player);
+			let score = -self.negamax(&copy, depth, player.opponent());
+			if best.is_none_or(|(_, best_score)| score > best_score) {
+				best = Some((position, score));
+			}
+		}
+		best
+	}
+}
+
+impl Default for Search {
+	fn default() -> Search {
+		Search::new()
+	}
+}
+
+pub fn negamax(board: &Board, depth: usize, player: Player) -> i64 {
+	Search::new().negamax(board, depth, player)
+}
+
+pub fn choose_move(board: &Board, player: Player, depth: usize) -> Option<(Position, i64)> {
+	Search::new().choose_move(board, player, depth)
+}
+
+// Depths for `adaptive_depth`: positions with at most `ADAPTIVE_SOLVE_EMPTIES` empty squares
+// are searched to the end, those with at most `ADAPTIVE_LATE_EMPTIES` two plies deeper than the
+// base depth, and those with at least `ADAPTIVE_OPENING_EMPTIES` two plies shallower.
+pub const ADAPTIVE_SOLVE_EMPTIES: u32 = 14;
+pub const ADAPTIVE_LATE_EMPTIES: u32 = 24;
+pub const ADAPTIVE_OPENING_EMPTIES: u32 = 45;
+
+// A depth that reaches the end of any game.
+const FULL_DEPTH: usize = 64;
+
+// The depth to search a position with `empties` empty squares at, around `base`: shallower
+// while the branching is wide, deeper as the board fills, up to a full solve. Never decreases
+// as `empties` does.
+pub fn adaptive_depth(base: usize, empties: u32) -> usize {
+	if empties <= ADAPTIVE_SOLVE_EMPTIES {
+		FULL_DEPTH
+	} else if empties <= ADAPTIVE_LATE_EMPTIES {
+		base + 2
+	} else if empties >= ADAPTIVE_OPENING_EMPTIES {
+		base.saturating_sub(2).max(1)
+	} else {
+		base
+	}
+}
+
+// Scores every legal move, searching the root moves in parallel. Each full-window root score is
+// exact whatever the worker's table holds, and equal scores stay in row-major order, so the
+// ranking doesn't depend on scheduling.
+pub fn analyze(board: &Board, player: Player, depth: usize) -> Analysis {
+	analyze_with(board, player, depth, Search::new)
+}
+
+// The plies a search `depth` plies past the root move looks ahead, or the empty squares when it
+// reaches the end of the game: once its leaves fall below the solver's threshold or the board
+// fills.
+fn reported_depth(board: &Board, depth: usize) -> usize {
+	let solved = board.empty_count() as usize <= DEFAULT_ENDGAME_EMPTIES as usize + depth || depth >= board.empty_count() as usize;
+	if solved { board.empty_count() as usize } else { depth + 1 }
+}
+
+// `analyze` with each worker's search made by `new_search`, to change its settings.
+pub fn analyze_with(board: &Board, player: Player, depth: usize, new_search: impl Fn() -> Search + Sync + Send) -> Analysis {
+	let started = Instant::now();
+	let mut moves: Vec<(Position, i64, Vec<Command>, u64)> = board.legal_moves(player).into_par_iter()
+		.map_init(new_search, |search, position| {
+			let mut child = board.clone();
+			child.play_at(position, player);
+			let nodes = search.nodes();
+			let score = -search.negamax(&child, depth, player.opponent());
+			(position, score, search.principal_variation(&child, player.opponent(), depth), search.nodes() - nodes)
+		})
+		.collect();
+	moves.sort_by_key(|&(_, score, _, _)| std::cmp::Reverse(score));
+	let stats = SearchStats {
+		nodes: moves.iter().map(|&(_, _, _, nodes)| nodes).sum(),
+		depth: reported_depth(board, depth),
+		elapsed: started.elapsed(),
+	};
+	Analysis { moves: moves.into_iter().map(|(position, score, line, _)| (position, score, line)).collect(), stats }
+}
+
+// Like `choose_move`, with the root moves searched in parallel; the result matches the serial
+// search.
+pub fn par_choose_move(board: &Board, player: Player, depth: usize) -> Option<(Position, i64)> {
+	analyze(board, player, depth).moves.into_iter().next().map(|(position, score, _)| (position, score))
+}
+
+impl Board {
+	// The share of a clock to spend on one move: what is left, split evenly over the moves this
+	// side can still expect to make.
+	pub fn move_budget(&self, remaining: Duration) -> Duration {
+		remaining / (self.empty_count() / 2).max(1)
+	}
+
+	// Searches at increasing depths until `deadline` has elapsed. A depth cut short by the
+	// deadline is discarded; if not even depth 1 completes, the first legal move is returned.
+	pub fn best_move(&self, player: Player, deadline: Duration) -> Option<Position> {
+		self.best_move_with_stats(player, deadline).0
+	}
+
+	// `best_move`, also reporting the deepest depth that completed.
+	pub fn best_move_with_stats(&self, player: Player, deadline: Duration) -> (Option<Position>, SearchStats) {
+		self.best_move_using(Search::new(), player, deadline)
+	}
+
+	// `best_move_with_stats` with the given search, whose deadline is replaced.
+	pub fn best_move_using(&self, search: Search, player: Player, deadline: Duration) -> (Option<Position>, SearchStats) {
+		let started = Instant::now();
+		let first = self.legal_moves(player).first().copied();
+		let mut search = search.with_deadline(started + deadline);
+		let (best_play, completed) = match search.iterative_deepening(self, player, self.empty_count() as usize) {
+			Some((position, _, depth)) => (Some(position), depth),
+			None => (first, 0),
+		};
+		(best_play, SearchStats { nodes: search.nodes(), depth: completed, elapsed: started.elapsed() })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Positions from the opening to the late midgame, black to move.
+	const POSITIONS: [&str; 4] = [
+		".............O.....XOX.....OX....XOXX....O...X..................X",
+		"..XXX......OXO....OOOOO..O.XX....OOXX....O...X..................X",
+		"..XXX.O...XXXXX..XXXOOOO.O.OX.OX.OOOOO...O.OOO.......OO.........X",
+		"..XXXOOOOOOOOOOXXOOOXOOX.OOOXXOX.OOXOOO..O.XOO....XX.OO....X....X",
+	];
+
+	fn positions() -> impl Iterator<Item = (Board, Player)> {
+		POSITIONS.iter().map(|notation| Board::from_notation(notation).unwrap())
+	}
+
+	#[test]
+	fn a_side_without_moves_passes_to_the_opponent() {
+		// White on a1 and black on b1: black has no move and white's reply c1 takes b1.
+		let (board, _) = Board::from_notation(&format!("OX{}X", ".".repeat(62))).unwrap();
+		assert!(board.legal_moves(Player::Black).is_empty());
+		assert_eq!(negamax(&board, 1, Player::Black), -3);
+		assert_eq!(negamax(&board, 1, Player::White), 3);
+	}
+
+	#[test]
+	fn the_table_keeps_the_score_of_each_search() {
+		for (board, player) in positions() {
+			for depth in 1..=5 {
+				let mut search = Search::new();
+				let score = search.negamax(&board, depth, player);
+				// A single slot keeps next to nothing, so the score can't depend on the table.
+				let mut one_slot = Search { tt: TranspositionTable::with_size_bits(0), ..Search::new() };
+				assert_eq!(one_slot.negamax(&board, depth, player), score);
+				let entry = search.tt.probe(board.zobrist(player)).unwrap();
+				assert_eq!((entry.depth, entry.score, entry.bound), (depth, score, Bound::Exact));
+			}
+		}
+	}
+
+	#[test]
+	fn the_transposition_table_saves_nodes() {
+		for (board, player) in positions() {
+			let mut with_table = Search::new();
+			let mut without = Search { tt: TranspositionTable::with_size_bits(0), ..Search::new() };
+			assert_eq!(with_table.negamax(&board, 6, player), without.negamax(&board, 6, player));
+			assert!(with_table.nodes() < without.nodes(), "{}: {} against {}", board.to_notation(player), with_table.nodes(), without.nodes());
+		}
+	}
+
+	const FULL_WINDOW: (i64, i64) = (i32::MIN as i64, i32::MAX as i64);
+
+	// Late positions from random games, black to move, with 8 and with 12 empty squares.
+	const ENDGAMES_8: [&str; 3] = [
+		".OXXXOOXX.OXXOO.XXXOXXOXXXOOOOOXXX.OXOOXXXXOOXOXXXXXXOO.O..OOOO.X",
+		"XXXXX.X.OOOOOXXO.OXXXXXOOOOOXXXO.OXXOXXOOXXXXXXO..OOOOOO.O.OOOXXX",
+		"O.XO...XXXXXOOOOXXOOXOO..XXXXXOXXOXXOOXXXXOOOOOXXOOOOOOXOOO.OOO.X",
+	];
+	const ENDGAMES_12: [&str; 3] = [
+		".OXXXO..X.OXXOO.XXXOXXXXXXOOOOXXXX.OXOOXXXXOXOOXXXXXXO..O..OXX..X",
+		"XXXXX....XXOOOOO.XXXXXXO.XOOXXXO.OXXOXXOOX.OOXXO..OOOOOO.O.OOOXXX",
+		"O......XXOXXXXX.X.OOOOO..XOXXOOXXOXXOOOXXXOOOOOXXOOOOOOXOOO.OOO.X",
+	];
+
+	#[test]
+	fn the_solver_returns_the_final_disc_margin() {
+		for notation in ENDGAMES_8 {
+			let (board, _) = Board::from_notation(notation).unwrap();
+			// With the solver off, a search deep enough to reach every game end counting discs.
+			let mut full_depth = Search::new().with_endgame_empties(0).with_heuristic(Heuristic::DiscCount);
+			let exact = full_depth.negamax(&board, 64, Player::Black);
+			assert_eq!(Search::new().solve(&board, FULL_WINDOW.0, FULL_WINDOW.1, Player::Black), exact, "{notation}");
+			// Below the threshold even a depth-1 search is played out to the end.
+			assert_eq!(Search::new().negamax(&board, 1, Player::Black), exact, "{notation}");
+		}
+	}
+
+	#[test]
+	fn depth_limited_searches_solve_once_below_the_threshold() {
+		for notation in ENDGAMES_12 {
+			let (board, _) = Board::from_notation(notation).unwrap();
+			let exact = Search::new().solve(&board, FULL_WINDOW.0, FULL_WINDOW.1, Player::Black);
+			assert_eq!(Search::new().negamax(&board, 8, Player::Black), exact, "{notation}");
+			// Each move scores its exact margin too, not only the best one.
+			for position in board.legal_moves(Player::Black) {
+				let mut child = board.clone();
+				child.play_at(position, Player::Black);
+				let exact = Search::new().solve(&child, FULL_WINDOW.0, FULL_WINDOW.1, Player::White);
+				assert_eq!(Search::new().negamax(&child, 7, Player::White), exact, "{notation}");
+			}
+		}
+	}
+
+	// Plain alpha-beta with no table, counting nodes, with the moves in row-major order or
+	// sorted by `order_moves`.
+	struct CountedSearch {
+		heuristic: Heuristic,
+		sorted: bool,
+		nodes: u64,
+	}
+
+	impl CountedSearch {
+		fn alpha_beta(&mut self, board: &Board, depth: usize, alpha: i64, beta: i64, player: Player) -> i64 {
+			self.nodes += 1;
+			let mut moves = board.legal_moves(player);
+			if depth == 0 || (moves.is_empty() && board.legal_moves(player.opponent()).is_empty()) {
+				return self.heuristic.evaluate(board, player)
+			}
+			if moves.is_empty() {
+				return -self.alpha_beta(board, depth, -beta, -alpha, player.opponent())
+			}
+			if self.sorted {
+				order_moves(&mut moves);
+			}
+			let mut alpha = alpha;
+			let mut score = i32::MIN as i64;
+			for position in moves {
+				let mut child = board.clone();
+				child.play_at(position, player);
+				score = std::cmp::max(score, -self.alpha_beta(&child, depth - 1, -beta, -alpha, player.opponent()));
+				alpha = std::cmp::max(alpha, score);
+				if alpha >= beta {
+					break
+				}
+			}
+			score
+		}
+	}
+
+	#[test]
+	fn static_ordering_saves_nodes() {
+		// The order follows the positional weights, so it helps the evaluations built on them.
+		for heuristic in [Heuristic::Positional, Heuristic::Blended(Default::default())] {
+			let mut sorted = CountedSearch { heuristic, sorted: true, nodes: 0 };
+			let mut unsorted = CountedSearch { heuristic, sorted: false, nodes: 0 };
+			for (board, player) in positions() {
+				let score = sorted.alpha_beta(&board, 6, FULL_WINDOW.0, FULL_WINDOW.1, player);
+				assert_eq!(unsorted.alpha_beta(&board, 6, FULL_WINDOW.0, FULL_WINDOW.1, player), score);
+			}
+			assert!(sorted.nodes < unsorted.nodes, "{heuristic:?}: {} against {}", sorted.nodes, unsorted.nodes);
+			// The engine with only the static order, and no table, PVS or solver, visits the same nodes.
+			let mut engine = Search { tt: TranspositionTable::with_size_bits(0), ..Search::new() }.with_heuristic(heuristic).with_pvs(false).with_dynamic_ordering(false).with_endgame_empties(0);
+			for (board, player) in positions() {
+				engine.negamax(&board, 6, player);
+			}
+			assert_eq!(engine.nodes(), sorted.nodes, "{heuristic:?}");
+		}
+	}
+
+	#[test]
+	fn dynamic_ordering_saves_nodes() {
+		for heuristic in [Heuristic::DiscCount, Heuristic::Blended(Default::default())] {
+			let mut dynamic = Search::new().with_heuristic(heuristic);
+			let mut static_only = Search::new().with_heuristic(heuristic).with_dynamic_ordering(false);
+			for (board, player) in positions() {
+				assert_eq!(dynamic.negamax(&board, 7, player), static_only.negamax(&board, 7, player), "{}", board.to_notation(player));
+			}
+			assert!(dynamic.nodes() < static_only.nodes(), "{heuristic:?}: {} against {}", dynamic.nodes(), static_only.nodes());
+		}
+	}
+
+	#[test]
+	fn parallel_root_search_picks_the_serial_move() {
+		let positions = std::iter::once((Board::new(), Player::Black)).chain(positions());
+		for (index, (board, player)) in positions.enumerate() {
+			for depth in 1..=4 {
+				assert_eq!(par_choose_move(&board, player, depth), choose_move(&board, player, depth), "position {index} at depth {depth}");
+			}
+		}
+		// The four opening moves are symmetric and tie, and both take the first of them.
+		let scores: Vec<i64> = Board::new().legal_moves(Player::Black).into_iter().map(|position| {
+			let mut child = Board::new();
+			child.play_at(position, Player::Black);
+			-negamax(&child, 3, Player::White)
+		}).collect();
+		assert!(scores.iter().all(|&score| score == scores[0]), "{scores:?}");
+		assert_eq!(par_choose_move(&Board::new(), Player::Black, 3).map(|(position, _)| position), Some("d3".parse().unwrap()));
+	}
+
+	#[test]
+	fn the_principal_variation_follows_a_forced_line() {
+		// Four empty squares, and each side has a single legal move at every turn until the end.
+		let (board, player) = Board::from_notation("XXXXX..OXXXXX.OOXOXXXOOOXXXXXXXXXXXOXO.OXXOXOOOOXXXOOOOOOXOOOOOOO").unwrap();
+		let mut search = Search::new();
+		search.negamax(&board, 4, player);
+		let line: Vec<Command> = ["g5", "f2", "f1", "g1"].iter().map(|square| Command::PlayAt(square.parse().unwrap())).collect();
+		assert_eq!(search.principal_variation(&board, player, 4), line);
+		assert_eq!(search.principal_variation(&board, player, 2), line[..2]);
+	}
+
+	#[test]
+	fn search_stats_count_more_nodes_deeper() {
+		let (board, player) = positions().next().unwrap();
+		let mut previous = 0;
+		for depth in 1..=5 {
+			let stats = analyze(&board, player, depth).stats;
+			assert!(stats.nodes > previous, "depth {depth}: {} after {previous}", stats.nodes);
+			assert_eq!(stats.depth, depth + 1);
+			previous = stats.nodes;
+		}
+		// From 12 empties a depth-3 search reaches the solver, so it sees to the end of the game.
+		let (board, player) = Board::from_notation(ENDGAMES_12[0]).unwrap();
+		assert_eq!(analyze(&board, player, 3).stats.depth, 12);
+	}
+
+	#[test]
+	fn pvs_scores_like_plain_alpha_beta() {
+		for heuristic in [Heuristic::DiscCount, Heuristic::Blended(Default::default())] {
+			for (board, player) in positions() {
+				for depth in 3..=6 {
+					let plain = Search::new().with_heuristic(heuristic).with_pvs(false).negamax(&board, depth, player);
+					assert_eq!(Search::new().with_heuristic(heuristic).negamax(&board, depth, player), plain, "{} at depth {depth}", board.to_notation(player));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn aspiration_windows_keep_the_full_window_score() {
+		for (board, player) in positions() {
+			for depth in 1..=6 {
+				let (_, score, completed) = Search::new().iterative_deepening(&board, player, depth).unwrap();
+				assert_eq!((score, completed), (Search::new().negamax(&board, depth, player), depth), "{} at depth {depth}", board.to_notation(player));
+			}
+		}
+		// In the midgame position the score drops out of the window below it at depth 2 and
+		// above it at depth 3, so both re-searches run.
+		let (board, player) = positions().nth(2).unwrap();
+		let scores: Vec<i64> = (1..=3).map(|depth| Search::new().negamax(&board, depth, player)).collect();
+		assert!(scores[1] < scores[0] - ASPIRATION_WINDOW && scores[2] > scores[1] + ASPIRATION_WINDOW, "{scores:?}");
+		assert_eq!(Search::new().iterative_deepening(&board, player, 3).map(|(_, score, _)| score), Some(scores[2]));
+	}
+
+	#[test]
+	fn mtdf_converges_on_the_negamax_score() {
+		for heuristic in [Heuristic::DiscCount, Heuristic::Blended(Default::default())] {
+			for (board, player) in positions() {
+				for depth in 3..=6 {
+					let plain = Search::new().with_heuristic(heuristic).negamax(&board, depth, player);
+					assert_eq!(Search::new().with_heuristic(heuristic).with_mtdf(true).negamax(&board, depth, player), plain, "{} at depth {depth}", board.to_notation(player));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn a_side_that_must_pass_loses_under_the_no_pass_rule() {
+		// The position of the pass test: black has no move, white still has c1.
+		let (board, player) = Board::from_notation(&format!("OX{}X", ".".repeat(62))).unwrap();
+		let search = |no_pass| Search::new().with_heuristic(Heuristic::DiscCount).with_endgame_empties(0).with_no_pass(no_pass);
+		assert_eq!(search(false).negamax(&board, 1, player), -3);
+		assert_eq!(search(true).negamax(&board, 1, player), NO_MOVE_LOSS);
+		// White moves normally, and black then has neither a move nor a disc.
+		assert_eq!(search(true).negamax(&board, 1, player.opponent()), 3);
+	}
+
+	#[test]
+	fn adaptive_depth_never_decreases_as_the_board_fills() {
+		let depths: Vec<usize> = [60, 50, 45, 44, 30, 25, 24, 15, 14, 0].iter().map(|&empties| adaptive_depth(6, empties)).collect();
+		assert_eq!(depths, [4, 4, 4, 6, 6, 6, 8, 8, 64, 64]);
+		for base in 1..=10 {
+			for empties in 1..=60 {
+				assert!(adaptive_depth(base, empties - 1) >= adaptive_depth(base, empties), "base {base}, {empties} empties");
+			}
+		}
+	}
+
+	#[test]
+	fn root_search_matches_the_per_move_loop() {
+		for (board, player) in positions() {
+			for depth in 1..=5 {
+				assert_eq!(Search::new().negamax_root(&board, depth + 1, player), Search::new().choose_move(&board, player, depth), "{} at depth {depth}", board.to_notation(player));
+			}
+		}
+	}
+}
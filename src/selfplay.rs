@@ -0,0 +1,274 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{Board, Player};
+use crate::error::OthelloError;
+use crate::eval::{Heuristic, PhasedWeights, Weights, SCALAR_WEIGHTS};
+use crate::notation::Command;
+use crate::rng::Rng;
+use crate::search::Search;
+use crate::strategy::Strategy;
+
+// One side of a game: how it picks moves, how deep it searches and what it evaluates with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerConfig {
+    pub strategy: Strategy,
+    pub depth: usize,
+    pub heuristic: Heuristic,
+}
+
+impl PlayerConfig {
+    pub fn new(strategy: Strategy, depth: usize) -> PlayerConfig {
+        PlayerConfig { strategy, depth, heuristic: Heuristic::default() }
+    }
+}
+
+const HEURISTIC_NAMES: [&str; 4] = ["disc", "positional", "blended", "phased"];
+
+fn heuristic_name(heuristic: Heuristic) -> &'static str {
+    match heuristic {
+        Heuristic::DiscCount => HEURISTIC_NAMES[0],
+        Heuristic::Positional => HEURISTIC_NAMES[1],
+        Heuristic::Blended(_) => HEURISTIC_NAMES[2],
+        Heuristic::Phased(_) => HEURISTIC_NAMES[3],
+    }
+}
+
+// "random", "greedy" or "search", optionally followed by ":DEPTH" and ":HEURISTIC", where the
+// heuristic is one of `HEURISTIC_NAMES` with its default weights.
+impl FromStr for PlayerConfig {
+    type Err = OthelloError;
+
+    fn from_str(s: &str) -> Result<PlayerConfig, OthelloError> {
+        let invalid = || OthelloError::InvalidConfig(s.to_string());
+        let mut parts = s.split(':');
+        let mut config = PlayerConfig::new(parts.next().unwrap_or("").parse()?, 8);
+        if let Some(depth) = parts.next() {
+            config.depth = depth.parse().ok().filter(|&depth| depth > 0).ok_or_else(invalid)?;
+        }
+        if let Some(heuristic) = parts.next() {
+            config.heuristic = match heuristic {
+                "disc" => Heuristic::DiscCount,
+                "positional" => Heuristic::Positional,
+                "blended" => Heuristic::Blended(Weights::default()),
+                "phased" => Heuristic::Phased(PhasedWeights::default()),
+                _ => return Err(invalid()),
+            };
+        }
+        if parts.next().is_some() {
+            return Err(invalid())
+        }
+        Ok(config)
+    }
+}
+
+// The form `from_str` reads, with the depth and heuristic left out where they don't matter.
+impl fmt::Display for PlayerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.strategy {
+            Strategy::Random => write!(f, "random"),
+            Strategy::Greedy => write!(f, "greedy"),
+            Strategy::Search => write!(f, "search:{}:{}", self.depth, heuristic_name(self.heuristic)),
+        }
+    }
+}
+
+pub struct GameRecord {
+    pub moves: Vec<Command>,
+    pub board: Board,
+}
+
+impl GameRecord {
+    // Black's final disc count minus white's.
+    pub fn differential(&self) -> i64 {
+        self.board.player_score(Player::Black) - self.board.player_score(Player::White)
+    }
+}
+
+// Plays the opening moves, then lets the engine play both sides as configured, indexed by
+// `Player`, until neither side can move. `rng` drives the random strategy.
+pub fn play_game(opening: &[Command], configs: [PlayerConfig; 2], rng: &mut Rng) -> Result<GameRecord, OthelloError> {
+    let (mut board, mut player) = Board::from_transcript(opening, Player::Black)?;
+    let mut moves = opening.to_vec();
+    let mut searches = configs.map(|config| Search::new().with_heuristic(config.heuristic));
+    while !board.is_terminal() {
+        let config = configs[player as usize];
+        match config.strategy.choose_move(&board, player, config.depth, &mut searches[player as usize], rng) {
+            Some(position) => {
+                board.play_at(position, player);
+                moves.push(Command::PlayAt(position));
+            }
+            None => moves.push(Command::Pass),
+        }
+        player = player.opponent();
+    }
+    Ok(GameRecord { moves, board })
+}
+
+// Every sequence of `plies` moves from the initial position, in row-major move order.
+pub fn openings(plies: usize) -> Vec<Vec<Command>> {
+    fn extend(board: &Board, player: Player, plies: usize, prefix: &mut Vec<Command>, out: &mut Vec<Vec<Command>>) {
+        if plies == 0 {
+            out.push(prefix.clone());
+            return
+        }
+        for position in board.legal_moves(player) {
+            let mut child = board.clone();
+            child.play_at(position, player);
+            prefix.push(Command::PlayAt(position));
+            extend(&child, player.opponent(), plies - 1, prefix, out);
+            prefix.pop();
+        }
+    }
+    let mut out = Vec::new();
+    extend(&Board::new(), Player::Black, plies, &mut Vec::new(), &mut out);
+    out
+}
+
+// Plays up to `plies` uniformly random legal moves from the initial position, passing when
+// a side has no move.
+pub fn random_opening(plies: usize, rng: &mut Rng) -> Vec<Command> {
+    let mut board = Board::new();
+    let mut player = Player::Black;
+    let mut moves = Vec::new();
+    for _ in 0..plies {
+        if board.legal_moves(player).is_empty() {
+            if board.is_terminal() {
+                break
+            }
+            moves.push(Command::Pass);
+        } else {
+            let position = board.random_move(player, rng).expect("there is a legal move");
+            board.play_at(position, player);
+            moves.push(Command::PlayAt(position));
+        }
+        player = player.opponent();
+    }
+    moves
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    pub black_wins: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    pub differential: i64,
+}
+
+impl Tally {
+    pub fn record(&mut self, game: &GameRecord) {
+        match game.board.result() {
+            Some(Command::Victory(Some(Player::Black))) => self.black_wins += 1,
+            Some(Command::Victory(Some(Player::White))) => self.white_wins += 1,
+            _ => self.draws += 1,
+        }
+        self.differential += game.differential();
+    }
+
+    pub fn games(&self) -> u32 {
+        self.black_wins + self.white_wins + self.draws
+    }
+}
+
+// The opening of the `index`-th game of a series and the generator for the rest of it, both
+// derived from `seed`. With `random_plies` set, the game opens with that many random moves;
+// otherwise the games cycle through the two-ply openings.
+fn game_opening(index: usize, random_plies: usize, seed: u64) -> (Vec<Command>, Rng) {
+    let mut rng = Rng::for_game(seed, index);
+    let opening = if random_plies > 0 {
+        random_opening(random_plies, &mut rng)
+    } else {
+        let fixed_openings = openings(2);
+        fixed_openings[index % fixed_openings.len()].clone()
+    };
+    (opening, rng)
+}
+
+// Plays `games` games between the two configurations, indexed by `Player`.
+pub fn play_match(games: usize, configs: [PlayerConfig; 2], random_plies: usize, seed: u64) -> Tally {
+    let mut tally = Tally::default();
+    for index in 0..games {
+        let (opening, mut rng) = game_opening(index, random_plies, seed);
+        tally.record(&play_game(&opening, configs, &mut rng).expect("openings are legal"));
+    }
+    tally
+}
+
+// The games between two configurations of a tournament, counted for the first of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pairing {
+    pub first: usize,
+    pub second: usize,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub differential: i64,
+}
+
+// Plays every configuration against every other one from `games` openings, each opening
+// twice so that both play it as black. Pairings come in the order of the configurations.
+pub fn round_robin(configs: &[PlayerConfig], games: usize, random_plies: usize, seed: u64) -> Vec<Pairing> {
+    let mut pairings = Vec::new();
+    for first in 0..configs.len() {
+        for second in first + 1..configs.len() {
+            let mut pairing = Pairing { first, second, wins: 0, losses: 0, draws: 0, differential: 0 };
+            for index in 0..games {
+                for first_as_black in [true, false] {
+                    let (opening, mut rng) = game_opening(index, random_plies, seed);
+                    let sides = if first_as_black { [configs[first], configs[second]] } else { [configs[second], configs[first]] };
+                    let game = play_game(&opening, sides, &mut rng).expect("openings are legal");
+                    let differential = if first_as_black { game.differential() } else { -game.differential() };
+                    match differential.cmp(&0) {
+                        std::cmp::Ordering::Greater => pairing.wins += 1,
+                        std::cmp::Ordering::Less => pairing.losses += 1,
+                        std::cmp::Ordering::Equal => pairing.draws += 1,
+                    }
+                    pairing.differential += differential;
+                }
+            }
+            pairings.push(pairing);
+        }
+    }
+    pairings
+}
+
+// One trial of `tune`: the weight changed, its new value, and how the candidate did against the
+// weights it was changed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TuningStep {
+    pub round: usize,
+    pub weight: &'static str,
+    pub value: i64,
+    pub pairing: Pairing,
+    pub accepted: bool,
+}
+
+// Coordinate descent on the scalar weights of the blended heuristic, with matches between
+// searches of `depth` plies as the measure. Each round tries every weight a step up and a step
+// down, a quarter of its value or at least 1, and keeps a change that wins more games than it
+// loses against the current weights. Every trial is passed to `on_step`. Each trial plays
+// fresh openings, so that changes aren't fitted to a single set of them.
+pub fn tune(start: Weights, depth: usize, rounds: usize, games: usize, random_plies: usize, seed: u64, mut on_step: impl FnMut(&TuningStep)) -> Weights {
+    let config = |weights| PlayerConfig { heuristic: Heuristic::Blended(weights), ..PlayerConfig::new(Strategy::Search, depth) };
+    let mut current = start;
+    let mut trial = 0;
+    for round in 1..=rounds {
+        for weight in SCALAR_WEIGHTS {
+            let value = *current.scalar_mut(weight).expect("scalar weights have a field");
+            let step = (value.abs() / 4).max(1);
+            for value in [value + step, value - step] {
+                let mut candidate = current;
+                *candidate.scalar_mut(weight).expect("scalar weights have a field") = value;
+                trial += 1;
+                let pairing = round_robin(&[config(candidate), config(current)], games, random_plies, seed.wrapping_add(trial))[0];
+                let accepted = pairing.wins > pairing.losses;
+                on_step(&TuningStep { round, weight, value, pairing, accepted });
+                if accepted {
+                    current = candidate;
+                    break
+                }
+            }
+        }
+    }
+    current
+}
@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use crate::board::{Board, Player, Position};
+use crate::error::OthelloError;
+use crate::rng::Rng;
+use crate::search::Search;
+
+// How the machine picks its moves, from a beginner's opponent to the full search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    // Any legal move, uniformly.
+    Random,
+    // The move that flips the most discs.
+    Greedy,
+    #[default]
+    Search,
+}
+
+impl FromStr for Strategy {
+    type Err = OthelloError;
+
+    fn from_str(s: &str) -> Result<Strategy, OthelloError> {
+        match s {
+            "random" => Ok(Strategy::Random),
+            "greedy" => Ok(Strategy::Greedy),
+            "search" => Ok(Strategy::Search),
+            _ => Err(OthelloError::InvalidStrategy(s.to_string())),
+        }
+    }
+}
+
+impl Strategy {
+    // The move to play, None when `player` has to pass. `search` and `depth` only matter to
+    // `Search` and `rng` only to `Random`.
+    pub fn choose_move(self, board: &Board, player: Player, depth: usize, search: &mut Search, rng: &mut Rng) -> Option<Position> {
+        match self {
+            Strategy::Random => board.random_move(player, rng),
+            Strategy::Greedy => board.greedy_move(player),
+            Strategy::Search => search.choose_move(board, player, depth).map(|(position, _)| position),
+        }
+    }
+}
+
+impl Board {
+    pub fn random_move(&self, player: Player, rng: &mut Rng) -> Option<Position> {
+        let moves = self.legal_moves(player);
+        (!moves.is_empty()).then(|| moves[rng.below(moves.len())])
+    }
+
+    // Ties go to the first move in row-major order.
+    pub fn greedy_move(&self, player: Player) -> Option<Position> {
+        self.legal_moves(player).into_iter().rev().max_by_key(|&position| self.flip_count(position, player))
+    }
+}
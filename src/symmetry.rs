@@ -0,0 +1,101 @@
+use crate::board::{Bits, Board, Player, Position};
+
+// One of the 8 symmetries of the square: an optional mirror of the files and of the ranks,
+// followed by an optional swap of the two axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Symmetry {
+    pub mirror: bool,
+    pub flip: bool,
+    pub transpose: bool,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = {
+        let mut all = [Symmetry { mirror: false, flip: false, transpose: false }; 8];
+        let mut i = 0;
+        while i < 8 {
+            all[i] = Symmetry { mirror: i & 1 != 0, flip: i & 2 != 0, transpose: i & 4 != 0 };
+            i += 1;
+        }
+        all
+    };
+
+    // Where the square `p` of a `size`x`size` board goes.
+    pub fn apply(self, p: Position, size: usize) -> Position {
+        let last = size as i8 - 1;
+        let x = if self.mirror { last - p.x } else { p.x };
+        let y = if self.flip { last - p.y } else { p.y };
+        if self.transpose { Position { x: y, y: x } } else { Position { x, y } }
+    }
+
+    // Mirrors applied before a swap of the axes act on the other axis when undone.
+    pub fn inverse(self) -> Symmetry {
+        if self.transpose {
+            Symmetry { mirror: self.flip, flip: self.mirror, transpose: true }
+        } else {
+            self
+        }
+    }
+
+    fn apply_bits<const N: usize>(self, bits: Bits) -> Bits {
+        let mut out = 0;
+        let mut remaining = bits;
+        while remaining != 0 {
+            let index = remaining.trailing_zeros() as usize;
+            let p = self.apply(Position { x: (index % N) as i8, y: (index / N) as i8 }, N);
+            out |= 1 << (p.x as usize + N * p.y as usize);
+            remaining &= remaining - 1;
+        }
+        out
+    }
+}
+
+impl<const N: usize> Board<N> {
+    pub fn transformed(&self, symmetry: Symmetry) -> Board<N> {
+        let black = symmetry.apply_bits::<N>(self.discs(Player::Black));
+        let white = symmetry.apply_bits::<N>(self.discs(Player::White));
+        Board::from_discs(black, white)
+    }
+
+    // The smallest of the 8 symmetric forms, comparing black's discs then white's as numbers,
+    // and the symmetry that produces it from `self`. Moves found on the canonical board map
+    // back with `symmetry.inverse()`.
+    pub fn canonical_with_symmetry(&self) -> (Board<N>, Symmetry) {
+        Symmetry::ALL.iter()
+            .map(|&symmetry| (self.transformed(symmetry), symmetry))
+            .min_by_key(|(board, _)| (board.discs(Player::Black), board.discs(Player::White)))
+            .expect("there are 8 symmetries")
+    }
+
+    pub fn canonical(&self) -> Board<N> {
+        self.canonical_with_symmetry().0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_symmetric_forms_of_a_position_share_one_canonical_board() {
+        let (board, player) = Board::from_notation("..XXX......OXO....OOOOO..O.XX....OOXX....O...X..................X").unwrap();
+        let canonical = board.canonical().to_notation(player);
+        for symmetry in Symmetry::ALL {
+            let transformed = board.transformed(symmetry);
+            assert_eq!(transformed.canonical().to_notation(player), canonical, "{symmetry:?}");
+            assert_eq!(transformed.transformed(symmetry.inverse()).to_notation(player), board.to_notation(player), "{symmetry:?}");
+        }
+    }
+
+    #[test]
+    fn the_inverse_undoes_each_symmetry() {
+        for symmetry in Symmetry::ALL {
+            for size in [6, 8, 10] {
+                for index in 0..size * size {
+                    let p = Position { x: (index % size) as i8, y: (index / size) as i8 };
+                    assert_eq!(symmetry.inverse().apply(symmetry.apply(p, size), size), p, "{symmetry:?} on {size}x{size}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::board::Position;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound { Exact, Lower, Upper }
+
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    pub key: u64,
+    pub depth: usize,
+    pub score: i64,
+    pub bound: Bound,
+    // The move that produced the score, unless every move failed low.
+    pub best: Option<Position>,
+}
+
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+}
+
+const DEFAULT_SIZE_BITS: u32 = 18;
+
+impl TranspositionTable {
+    pub fn new() -> TranspositionTable {
+        TranspositionTable::with_size_bits(DEFAULT_SIZE_BITS)
+    }
+
+    pub fn with_size_bits(bits: u32) -> TranspositionTable {
+        TranspositionTable { entries: vec![None; 1 << bits] }
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        (key as usize) & (self.entries.len() - 1)
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.entries[self.slot(key)].filter(|entry| entry.key == key)
+    }
+
+    pub fn store(&mut self, entry: Entry) {
+        let slot = self.slot(entry.key);
+        match self.entries[slot] {
+            Some(old) if old.key == entry.key && old.depth > entry.depth => {}
+            _ => self.entries[slot] = Some(entry),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> TranspositionTable {
+        TranspositionTable::new()
+    }
+}
@@ -0,0 +1,46 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game::Game;
+use crate::notation::Command;
+use crate::search::Search;
+
+// A game for JavaScript, with moves as coordinates like "d3" and boards in board notation.
+// Searches stop at the depth the page asks for, since they run on its thread.
+#[wasm_bindgen]
+pub struct WebGame {
+    game: Game,
+}
+
+#[wasm_bindgen]
+pub fn new_board() -> WebGame {
+    WebGame { game: Game::new() }
+}
+
+#[wasm_bindgen]
+impl WebGame {
+    // Plays a coordinate or "pass" for the side to move, throwing when it isn't legal.
+    pub fn play(&mut self, position: &str) -> Result<(), JsError> {
+        match Command::parse(position)? {
+            cmd @ (Command::PlayAt(_) | Command::Pass) => Ok(self.game.play(cmd)?),
+            _ => Err(JsError::new(&format!("expected a coordinate or 'pass', found '{position}'"))),
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<String> {
+        self.game.board().legal_moves(self.game.to_move()).into_iter().map(|position| position.to_string()).collect()
+    }
+
+    // The engine's move for the side to move, searching `depth` plies past it, or undefined
+    // when it has to pass.
+    pub fn best_move(&self, depth: usize) -> Option<String> {
+        Search::new().choose_move(self.game.board(), self.game.to_move(), depth).map(|(position, _)| position.to_string())
+    }
+
+    pub fn to_notation(&self) -> String {
+        self.game.board().to_notation(self.game.to_move())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.game.is_over()
+    }
+}
@@ -0,0 +1,28 @@
+pub(crate) const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate_keys() -> [[u64; 128]; 2] {
+    let mut keys = [[0; 128]; 2];
+    let mut state = 0x4f74_6865_6c6c_6f21;
+    let mut player = 0;
+    while player < 2 {
+        let mut square = 0;
+        while square < 128 {
+            let (next, key) = splitmix64(state);
+            keys[player][square] = key;
+            state = next;
+            square += 1;
+        }
+        player += 1;
+    }
+    keys
+}
+
+pub(crate) static KEYS: [[u64; 128]; 2] = generate_keys();
+
+pub(crate) const WHITE_TO_MOVE: u64 = splitmix64(0x5768_6974_6521).1;
@@ -0,0 +1,56 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// Runs the game with `args`, feeds it `input` and closes its stdin.
+fn run(args: &[&str], input: impl AsRef<[u8]>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_othello"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the game starts");
+    child.stdin.take().unwrap().write_all(input.as_ref()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn the_game_quits_when_its_input_ends() {
+    let output = run(&["white", "1"], "");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.ends_with("goodbye\nX: 2, O: 2\n"), "{stdout}");
+}
+
+#[test]
+fn the_game_quits_when_its_input_cant_be_read() {
+    let output = run(&["white", "1"], b"\xff\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("couldn't read input"));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.ends_with("goodbye\nX: 2, O: 2\n"), "{stdout}");
+}
+
+#[test]
+fn an_illegal_move_is_refused_and_asked_again() {
+    let stdout = String::from_utf8(run(&["white", "1"], "a1\nd3\n").stdout).unwrap();
+    let refused = stdout.find("a1 is not a legal move, it flips no disc in any direction\nX?\n").expect(&stdout);
+    let played = stdout.find("X: 4, O: 1\n").expect(&stdout);
+    assert!(refused < played, "{stdout}");
+}
+
+#[test]
+fn verbose_traces_the_engine_to_stderr() {
+    let traced = String::from_utf8(run(&["--verbose", "white", "2"], "d3\n").stderr).unwrap();
+    assert!(traced.lines().any(|line| line.starts_with("search: depth 3, ") && line.contains(" scores ")), "{traced}");
+    let quiet = run(&["white", "2"], "d3\n");
+    assert!(quiet.stderr.is_empty(), "{}", String::from_utf8_lossy(&quiet.stderr));
+}
+
+#[test]
+fn passing_is_refused_while_a_move_is_legal() {
+    let stdout = String::from_utf8(run(&["white", "1"], "pass\nd3\n").stdout).unwrap();
+    let refused = stdout.find("can't pass while there are legal moves\nX?\n").expect(&stdout);
+    let played = stdout.find("X: 4, O: 1\n").expect(&stdout);
+    assert!(refused < played, "{stdout}");
+}